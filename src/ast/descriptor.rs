@@ -1,13 +1,25 @@
 use std::fmt;
 use super::label::LabelType;
-use super::types::PropertyType;
+use super::types::{PropertyType, Record, RecordField, SimpleType};
 use super::var::Var;
+use super::expr::{AttributeLookup, Binop, BinOpKind, Constant, Expr};
+use crate::error::SyntaxError;
 
 pub struct DescriptorType {
     pub label: LabelType,
     pub properties: PropertyType,
 }
 
+// Default - the unconstrained descriptor type `* {}`
+impl Default for DescriptorType {
+    fn default() -> Self {
+        DescriptorType {
+            label: LabelType::Star,
+            properties: PropertyType::Open(Record::new()),
+        }
+    }
+}
+
 // Debug (__repr__ equivalent) - developer representation
 impl fmt::Debug for DescriptorType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -15,6 +27,129 @@ impl fmt::Debug for DescriptorType {
     }
 }
 
+/// Reason two `DescriptorType`s cannot be combined.
+#[derive(Debug, PartialEq, Clone)]
+pub enum DescriptorTypeError {
+    /// Both sides type the key, and neither type is a subtype of the other
+    PropertyConflict { key: String, left: Box<SimpleType>, right: Box<SimpleType> },
+    /// One side requires the key while the other is a closed record without it
+    ClosedRecord(String),
+}
+
+impl fmt::Display for DescriptorTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DescriptorTypeError::PropertyConflict { key, left, right } => {
+                write!(f, "property `{}` has conflicting types {} and {}", key, left, right)
+            }
+            DescriptorTypeError::ClosedRecord(key) => {
+                write!(f, "property `{}` is required but not allowed by a closed record", key)
+            }
+        }
+    }
+}
+
+impl DescriptorType {
+    /// Intersection: elements described by both `self` and `other`, e.g. for a
+    /// variable bound twice in one pattern. Labels are conjoined; records are
+    /// merged key by key, keeping the narrower type, and a closed side wins.
+    pub fn meet(&self, other: &DescriptorType) -> Result<DescriptorType, DescriptorTypeError> {
+        let (p1, p2) = (&self.properties, &other.properties);
+        let (r1, r2) = (p1.record(), p2.record());
+        let mut record = Record::new();
+        for key in keys(r1, r2) {
+            let field = match (r1.field(key), r2.field(key)) {
+                (Some(f1), Some(f2)) => RecordField {
+                    ty: narrower(key, &f1.ty, &f2.ty)?,
+                    optional: f1.optional && f2.optional,
+                },
+                // A closed record forbids the key: drop it if optional, fail if required
+                (Some(f), None) if p2.is_closed() && f.optional => continue,
+                (None, Some(f)) if p1.is_closed() && f.optional => continue,
+                (Some(_), None) if p2.is_closed() => return Err(DescriptorTypeError::ClosedRecord(key.clone())),
+                (None, Some(_)) if p1.is_closed() => return Err(DescriptorTypeError::ClosedRecord(key.clone())),
+                (Some(f), None) | (None, Some(f)) => f.clone(),
+                (None, None) => unreachable!(),
+            };
+            record.insert(key.clone(), field);
+        }
+        let properties = if p1.is_closed() || p2.is_closed() {
+            PropertyType::Closed(record)
+        } else {
+            PropertyType::Open(record)
+        };
+        Ok(DescriptorType {
+            label: LabelType::And(Box::new(self.label.clone()), Box::new(other.label.clone())),
+            properties,
+        })
+    }
+
+    /// Union: elements described by `self` or by `other`. Labels are
+    /// disjoined; shared keys keep the wider type, and a key known to one
+    /// side only becomes optional when the other side is closed, or is
+    /// dropped when the other side is open. Closed only if both sides are.
+    pub fn join(&self, other: &DescriptorType) -> Result<DescriptorType, DescriptorTypeError> {
+        let (p1, p2) = (&self.properties, &other.properties);
+        let (r1, r2) = (p1.record(), p2.record());
+        let mut record = Record::new();
+        for key in keys(r1, r2) {
+            let field = match (r1.field(key), r2.field(key)) {
+                (Some(f1), Some(f2)) => RecordField {
+                    ty: wider(key, &f1.ty, &f2.ty)?,
+                    optional: f1.optional || f2.optional,
+                },
+                (Some(f), None) if p2.is_closed() => RecordField { ty: f.ty.clone(), optional: true },
+                (None, Some(f)) if p1.is_closed() => RecordField { ty: f.ty.clone(), optional: true },
+                _ => continue,
+            };
+            record.insert(key.clone(), field);
+        }
+        let properties = if p1.is_closed() && p2.is_closed() {
+            PropertyType::Closed(record)
+        } else {
+            PropertyType::Open(record)
+        };
+        Ok(DescriptorType {
+            label: LabelType::Or(Box::new(self.label.clone()), Box::new(other.label.clone())),
+            properties,
+        })
+    }
+}
+
+// Keys of `r1` in source order, then those only `r2` has
+fn keys<'a>(r1: &'a Record, r2: &'a Record) -> Vec<&'a String> {
+    r1.keys().chain(r2.keys().filter(|key| !r1.contains_key(key))).collect()
+}
+
+fn narrower(key: &str, t1: &SimpleType, t2: &SimpleType) -> Result<SimpleType, DescriptorTypeError> {
+    if t1.is_subtype_of(t2) {
+        Ok(t1.clone())
+    } else if t2.is_subtype_of(t1) {
+        Ok(t2.clone())
+    } else {
+        Err(conflict(key, t1, t2))
+    }
+}
+
+fn wider(key: &str, t1: &SimpleType, t2: &SimpleType) -> Result<SimpleType, DescriptorTypeError> {
+    if t1.is_subtype_of(t2) {
+        Ok(t2.clone())
+    } else if t2.is_subtype_of(t1) {
+        Ok(t1.clone())
+    } else {
+        Err(conflict(key, t1, t2))
+    }
+}
+
+fn conflict(key: &str, left: &SimpleType, right: &SimpleType) -> DescriptorTypeError {
+    DescriptorTypeError::PropertyConflict {
+        key: key.to_string(),
+        left: Box::new(left.clone()),
+        right: Box::new(right.clone()),
+    }
+}
+
+#[derive(Default)]
 pub struct Descriptor {
     pub variable: Option<Var>,
     pub descriptor_type: DescriptorType, // Always present, defaults to Star {}
@@ -30,3 +165,40 @@ impl fmt::Debug for Descriptor {
     }
 }
 
+
+/// Property values pinned inline in an element pattern, e.g. the
+/// `name: 'Alice'` in `(x:Person {name: 'Alice', age: int})`.
+/// Never stored on a Descriptor: the parser desugars it into a filter.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct PropertySpec {
+    pub values: Vec<(String, Constant)>,
+}
+
+impl PropertySpec {
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Equality filter `var.k1 = v1 AND var.k2 = v2 ...` in source order,
+    /// or None when no value is pinned. Values need a variable to refer to.
+    /// A pinned `NULL` becomes `var.k IS NULL`, since `= NULL` never holds.
+    pub fn to_filter(&self, variable: Option<&Var>) -> Result<Option<Expr>, SyntaxError> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let var = variable.ok_or(SyntaxError::AnonymousPropertyValues)?;
+        let filter = self
+            .values
+            .iter()
+            .map(|(key, value)| {
+                let op = if *value == Constant::Null { BinOpKind::Is } else { BinOpKind::Eq };
+                Expr::Binop(Binop::new(
+                    op,
+                    Expr::AttributeLookup(AttributeLookup::new(Expr::Variable(var.clone()), Var(key.clone()))),
+                    Expr::Constant(value.clone()),
+                ))
+            })
+            .reduce(|l, r| Expr::Binop(Binop::new(BinOpKind::And, l, r)));
+        Ok(filter)
+    }
+}
@@ -12,9 +12,10 @@ pub enum Expr {
     AttributeLookup(AttributeLookup),
     Binop(Binop),
     Unop(Unop),
+    List(Vec<Expr>),
 }
 
-/// Represents a constant expression (string, int, or boolean).
+/// Represents a constant expression (string, int, boolean, float, temporal or null).
 #[derive(PartialEq, Clone)]
 pub enum Constant {
     /// A constant string value (SConstant in Python)
@@ -23,6 +24,16 @@ pub enum Constant {
     Int(i64),
     /// A constant boolean value
     Bool(bool),
+    /// A constant floating point value, e.g. 3.14
+    Float(f64),
+    /// A date literal, e.g. DATE '2024-01-01'
+    Date(String),
+    /// A datetime literal, e.g. DATETIME '2024-01-01T10:00:00'
+    DateTime(String),
+    /// A duration literal, e.g. DURATION 'P1D'
+    Duration(String),
+    /// The null value
+    Null,
 }
 
 impl fmt::Debug for Constant {
@@ -31,27 +42,35 @@ impl fmt::Debug for Constant {
             Constant::String(s) => write!(f, "'{}'", s),
             Constant::Int(i) => write!(f, "{}", i),
             Constant::Bool(b) => write!(f, "{}", b),
+            // f64 Display never uses an exponent, but drops the `.0` of whole numbers
+            Constant::Float(x) if x.fract() == 0.0 => write!(f, "{}.0", x),
+            Constant::Float(x) => write!(f, "{}", x),
+            Constant::Date(s) => write!(f, "DATE '{}'", s),
+            Constant::DateTime(s) => write!(f, "DATETIME '{}'", s),
+            Constant::Duration(s) => write!(f, "DURATION '{}'", s),
+            Constant::Null => write!(f, "NULL"),
         }
     }
 }
 
 
-/// Expression of the form `e.a` that accesses the attribute `a` of the entity `e`.
+/// Expression of the form `e.a` that accesses the attribute `a` of `e`,
+/// where `e` is an entity variable or another lookup, e.g. `x.address.city`.
 #[derive(Debug, PartialEq, Clone)]
 pub struct AttributeLookup {
-    pub e: Var,
+    pub e: Box<Expr>,
     pub a: Var,
 }
 
 impl AttributeLookup {
-    pub fn new(e: Var, a: Var) -> Self {
-        AttributeLookup { e, a }
+    pub fn new(e: Expr, a: Var) -> Self {
+        AttributeLookup { e: Box::new(e), a }
     }
 }
 
 impl fmt::Display for AttributeLookup {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}.{}", self.e.0, self.a.0)
+        write!(f, "{}.{}", self.e, self.a.0)
     }
 }
 
@@ -161,9 +180,15 @@ impl fmt::Display for Unop {
 impl fmt::Display for Constant {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Constant::String(s) => write!(f, "{}", s),
+            Constant::String(s) => write!(f, "'{}'", s),
             Constant::Int(i) => write!(f, "{}", i),
             Constant::Bool(b) => write!(f, "{}", b),
+            Constant::Float(x) if x.fract() == 0.0 => write!(f, "{}.0", x),
+            Constant::Float(x) => write!(f, "{}", x),
+            Constant::Date(s) => write!(f, "DATE '{}'", s),
+            Constant::DateTime(s) => write!(f, "DATETIME '{}'", s),
+            Constant::Duration(s) => write!(f, "DURATION '{}'", s),
+            Constant::Null => write!(f, "NULL"),
         }
     }
 }
@@ -177,6 +202,16 @@ impl fmt::Display for Expr {
             Expr::AttributeLookup(a) => write!(f, "{}", a),
             Expr::Binop(b) => write!(f, "{}", b),
             Expr::Unop(u) => write!(f, "{}", u),
+            Expr::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
         }
     }
 }
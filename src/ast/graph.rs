@@ -0,0 +1,49 @@
+use std::fmt;
+use super::expr::Expr;
+use super::pattern::PathPattern;
+use super::var::Var;
+
+/// Comma-separated list of path patterns matched together, e.g.
+/// `(x)-[]->(y), (y)-[]->(z) WHERE x.a > z.a`.
+/// Variables shared between paths are implicit joins: every path must bind
+/// them to the same element.
+pub struct GraphPattern {
+    pub paths: Vec<PathPattern>,
+    pub filter: Option<Expr>,
+}
+
+impl GraphPattern {
+    /// Variables bound anywhere in the graph pattern, in order of first occurrence.
+    pub fn variables(&self) -> Vec<&Var> {
+        let mut vars: Vec<&Var> = Vec::new();
+        for var in self.paths.iter().flat_map(|p| p.variables()) {
+            if !vars.contains(&var) {
+                vars.push(var);
+            }
+        }
+        vars
+    }
+
+    /// Variables bound by more than one path pattern, i.e. the implicit join keys.
+    pub fn join_variables(&self) -> Vec<&Var> {
+        self.variables()
+            .into_iter()
+            .filter(|var| self.paths.iter().filter(|p| p.variables().contains(var)).count() > 1)
+            .collect()
+    }
+}
+
+impl fmt::Debug for GraphPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, path) in self.paths.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:?}", path)?;
+        }
+        if let Some(filter) = &self.filter {
+            write!(f, " WHERE {:?}", filter)?;
+        }
+        Ok(())
+    }
+}
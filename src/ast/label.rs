@@ -7,6 +7,8 @@ pub enum LabelType {
     Star,                                // *
     And(Box<LabelType>, Box<LabelType>), // e.g. Teacher & Student
     Or(Box<LabelType>, Box<LabelType>),  // e.g. Teacher | Student
+    Not(Box<LabelType>),                 // e.g. !Deleted
+    Wildcard,                            // %, has at least one label
 }
 
 impl fmt::Debug for LabelType {
@@ -16,7 +18,137 @@ impl fmt::Debug for LabelType {
             LabelType::Star => write!(f, "*"),
             LabelType::And(l1, l2) => write!(f, "({:?} & {:?})", l1, l2),
             LabelType::Or(l1, l2) => write!(f, "({:?} | {:?})", l1, l2),
+            LabelType::Not(l) => write!(f, "!{:?}", l),
+            LabelType::Wildcard => write!(f, "%"),
         }
     }
 }
 
+
+impl LabelType {
+    /// Whether some set of labels matches this label type.
+    /// Labels are boolean variables, `*` is true and `%` holds when at least
+    /// one label is present. Schema facts are conjoined by the caller, e.g.
+    /// `A & B & !(A & B)` encodes "A and B are disjoint".
+    pub fn is_satisfiable(&self) -> bool {
+        let labels = self.labels();
+        Formula::new(self, &labels).is_satisfiable()
+    }
+
+    /// Whether every set of labels matching `self` also matches `other`,
+    /// i.e. whether `self & !other` is unsatisfiable.
+    pub fn entails(&self, other: &LabelType) -> bool {
+        let mut labels = self.labels();
+        for label in other.labels() {
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        let counterexample = Formula::and(Formula::new(self, &labels), Formula::not(Formula::new(other, &labels)));
+        !counterexample.is_satisfiable()
+    }
+
+    /// Whether `self` and `other` match exactly the same sets of labels.
+    pub fn equivalent(&self, other: &LabelType) -> bool {
+        self.entails(other) && other.entails(self)
+    }
+
+    /// Distinct label names, in order of first occurrence.
+    pub fn labels(&self) -> Vec<&str> {
+        let mut labels = Vec::new();
+        self.collect_labels(&mut labels);
+        labels
+    }
+
+    fn collect_labels<'a>(&'a self, labels: &mut Vec<&'a str>) {
+        match self {
+            LabelType::Label(s) => {
+                if !labels.contains(&s.as_str()) {
+                    labels.push(s);
+                }
+            }
+            LabelType::Star | LabelType::Wildcard => {}
+            LabelType::And(l1, l2) | LabelType::Or(l1, l2) => {
+                l1.collect_labels(labels);
+                l2.collect_labels(labels);
+            }
+            LabelType::Not(l) => l.collect_labels(labels),
+        }
+    }
+}
+
+// Propositional formula over label indices, kept free of constants except at
+// the root. Variable `i` is `labels[i]`; the variable after the last label
+// stands for any label not mentioned at all, so `%` is their disjunction.
+enum Formula {
+    Const(bool),
+    Var(usize),
+    Not(Box<Formula>),
+    And(Box<Formula>, Box<Formula>),
+    Or(Box<Formula>, Box<Formula>),
+}
+
+impl Formula {
+    fn new(label: &LabelType, labels: &[&str]) -> Formula {
+        match label {
+            LabelType::Label(s) => Formula::Var(labels.iter().position(|label| label == s).unwrap()),
+            LabelType::Star => Formula::Const(true),
+            LabelType::Wildcard => (0..=labels.len()).map(Formula::Var).reduce(Formula::or).unwrap(),
+            LabelType::And(l1, l2) => Formula::and(Formula::new(l1, labels), Formula::new(l2, labels)),
+            LabelType::Or(l1, l2) => Formula::or(Formula::new(l1, labels), Formula::new(l2, labels)),
+            LabelType::Not(l) => Formula::not(Formula::new(l, labels)),
+        }
+    }
+
+    fn not(f: Formula) -> Formula {
+        match f {
+            Formula::Const(b) => Formula::Const(!b),
+            f => Formula::Not(Box::new(f)),
+        }
+    }
+
+    fn and(f1: Formula, f2: Formula) -> Formula {
+        match (f1, f2) {
+            (Formula::Const(false), _) | (_, Formula::Const(false)) => Formula::Const(false),
+            (Formula::Const(true), f) | (f, Formula::Const(true)) => f,
+            (f1, f2) => Formula::And(Box::new(f1), Box::new(f2)),
+        }
+    }
+
+    fn or(f1: Formula, f2: Formula) -> Formula {
+        match (f1, f2) {
+            (Formula::Const(true), _) | (_, Formula::Const(true)) => Formula::Const(true),
+            (Formula::Const(false), f) | (f, Formula::Const(false)) => f,
+            (f1, f2) => Formula::Or(Box::new(f1), Box::new(f2)),
+        }
+    }
+
+    /// Splits on one variable at a time. Constants fold away after each
+    /// assignment, so a branch stops as soon as its value is known.
+    fn is_satisfiable(&self) -> bool {
+        match self.first_var() {
+            None => matches!(self, Formula::Const(true)),
+            Some(var) => self.assign(var, true).is_satisfiable() || self.assign(var, false).is_satisfiable(),
+        }
+    }
+
+    fn first_var(&self) -> Option<usize> {
+        match self {
+            Formula::Const(_) => None,
+            Formula::Var(v) => Some(*v),
+            Formula::Not(f) => f.first_var(),
+            Formula::And(f1, f2) | Formula::Or(f1, f2) => f1.first_var().or_else(|| f2.first_var()),
+        }
+    }
+
+    fn assign(&self, var: usize, value: bool) -> Formula {
+        match self {
+            Formula::Const(b) => Formula::Const(*b),
+            Formula::Var(v) if *v == var => Formula::Const(value),
+            Formula::Var(v) => Formula::Var(*v),
+            Formula::Not(f) => Formula::not(f.assign(var, value)),
+            Formula::And(f1, f2) => Formula::and(f1.assign(var, value), f2.assign(var, value)),
+            Formula::Or(f1, f2) => Formula::or(f1.assign(var, value), f2.assign(var, value)),
+        }
+    }
+}
@@ -3,13 +3,20 @@ mod label;
 mod types;
 mod descriptor;
 mod pattern;
+mod graph;
+mod query;
 mod expr;
+mod normal;
+mod typecheck;
 
 // Re-export everything
 pub use var::Var;
 pub use label::LabelType;
-pub use types::{BaseType, SimpleType, PropertyType};
-pub use descriptor::{DescriptorType, Descriptor};
-pub use pattern::{NodePattern, PathPattern};
+pub use types::{BaseType, SimpleType, PropertyType, Record, RecordField, SubtypeError};
+pub use descriptor::{DescriptorType, DescriptorTypeError, Descriptor, PropertySpec};
+pub use pattern::{NodePattern, EdgePattern, EdgeDirection, PathPattern, Restrictor, Selector};
+pub use graph::GraphPattern;
+pub use query::{Query, ReturnItem};
 pub use expr::{Expr, Constant, AttributeLookup, Binop, Unop, BinOpKind, UnOpKind};
+pub use typecheck::TypeError;
 
@@ -0,0 +1,189 @@
+use std::collections::BTreeSet;
+use super::label::LabelType;
+
+// Normal forms for LabelType.
+//
+// A label type is first brought into disjunctive normal form, then closed
+// under consensus and absorption, which yields its Blake canonical form: the
+// disjunction of all prime implicants. Equivalent label types share the same
+// prime implicants, so after sorting they compare equal with `==`.
+// The conjunctive normal form is the dual, computed from the negation.
+//
+// `%` is not an independent atom, since any label implies it. Labels that only
+// matter through `%` are dropped first, so `A | %` is `%`. Then `%` is expanded
+// into the remaining labels or an atom standing for any other label, which is
+// printed back as `%`: the label type can only observe that atom through `%`.
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+enum Atom {
+    Wildcard,
+    Label(String),
+}
+
+// (atom, positive)
+type Literal = (Atom, bool);
+type Clause = BTreeSet<Literal>;
+
+impl LabelType {
+    /// Canonical disjunctive normal form: an `Or` of `And`s of possibly
+    /// negated labels, with `*` for true and `!*` for false.
+    pub fn to_dnf(&self) -> LabelType {
+        let (label, labels) = essential(self);
+        let clauses = canonical(dnf(&label, true, &labels));
+        from_clauses(&clauses, LabelType::Or, LabelType::And, true)
+    }
+
+    /// Canonical conjunctive normal form: an `And` of `Or`s of possibly
+    /// negated labels, with `*` for true and `!*` for false.
+    pub fn to_cnf(&self) -> LabelType {
+        // The clauses of the negation, each negated, are the clauses of the CNF
+        let (label, labels) = essential(self);
+        let clauses: BTreeSet<Clause> = canonical(dnf(&label, false, &labels))
+            .into_iter()
+            .map(|clause| clause.into_iter().map(|(atom, positive)| (atom, !positive)).collect())
+            .collect();
+        from_clauses(&clauses, LabelType::And, LabelType::Or, false)
+    }
+}
+
+// Replaces each label that can be swapped for false without changing the
+// meaning, i.e. whose presence only counts towards `%`, and returns the rest.
+fn essential(label: &LabelType) -> (LabelType, Vec<String>) {
+    let names: Vec<String> = label.labels().into_iter().map(String::from).collect();
+    let mut label = label.clone();
+    let mut essential = Vec::new();
+    for name in names {
+        let without = without_label(&label, &name);
+        if without.equivalent(&label) {
+            label = without;
+        } else {
+            essential.push(name);
+        }
+    }
+    (label, essential)
+}
+
+fn without_label(label: &LabelType, name: &str) -> LabelType {
+    match label {
+        LabelType::Label(s) if s == name => LabelType::Not(Box::new(LabelType::Star)),
+        LabelType::Label(_) | LabelType::Star | LabelType::Wildcard => label.clone(),
+        LabelType::Not(l) => LabelType::Not(Box::new(without_label(l, name))),
+        LabelType::And(l1, l2) => LabelType::And(Box::new(without_label(l1, name)), Box::new(without_label(l2, name))),
+        LabelType::Or(l1, l2) => LabelType::Or(Box::new(without_label(l1, name)), Box::new(without_label(l2, name))),
+    }
+}
+
+// Clauses of the DNF of `label` (or of its negation when `positive` is false).
+// No clauses is false, a single empty clause is true. `%` is `labels` or
+// `Atom::Wildcard`, which stands for a label outside `labels`.
+fn dnf(label: &LabelType, positive: bool, labels: &[String]) -> Vec<Clause> {
+    match (label, positive) {
+        (LabelType::Star, true) => vec![Clause::new()],
+        (LabelType::Star, false) => Vec::new(),
+        (LabelType::Label(s), _) => vec![Clause::from([(Atom::Label(s.clone()), positive)])],
+        (LabelType::Wildcard, _) => {
+            let atoms = labels.iter().map(|s| Atom::Label(s.clone())).chain([Atom::Wildcard]);
+            if positive {
+                atoms.map(|atom| Clause::from([(atom, true)])).collect()
+            } else {
+                vec![atoms.map(|atom| (atom, false)).collect()]
+            }
+        }
+        (LabelType::Not(l), _) => dnf(l, !positive, labels),
+        (LabelType::Or(l1, l2), true) | (LabelType::And(l1, l2), false) => {
+            let mut clauses = dnf(l1, positive, labels);
+            clauses.extend(dnf(l2, positive, labels));
+            clauses
+        }
+        (LabelType::And(l1, l2), true) | (LabelType::Or(l1, l2), false) => {
+            let right = dnf(l2, positive, labels);
+            dnf(l1, positive, labels)
+                .iter()
+                .flat_map(|c1| right.iter().map(move |c2| c1.union(c2).cloned().collect()))
+                .collect()
+        }
+    }
+}
+
+// Drops contradictory clauses, then applies consensus and absorption until
+// nothing changes. Duplicate clauses collapse in the set (idempotence).
+fn canonical(clauses: Vec<Clause>) -> BTreeSet<Clause> {
+    let mut clauses: BTreeSet<Clause> = clauses.into_iter().filter_map(simplify).collect();
+    loop {
+        let mut added = false;
+        let current: Vec<Clause> = clauses.iter().cloned().collect();
+        for (i, c1) in current.iter().enumerate() {
+            for c2 in &current[i + 1..] {
+                if let Some(consensus) = consensus(c1, c2).and_then(simplify)
+                    && !clauses.iter().any(|c| c.is_subset(&consensus))
+                {
+                    clauses.insert(consensus);
+                    added = true;
+                }
+            }
+        }
+        clauses = absorb(clauses);
+        if !added {
+            return clauses;
+        }
+    }
+}
+
+// None for a contradictory clause
+fn simplify(clause: Clause) -> Option<Clause> {
+    let contradictory = clause.iter().any(|(atom, positive)| clause.contains(&(atom.clone(), !positive)));
+    if contradictory { None } else { Some(clause) }
+}
+
+// Resolves two clauses that clash on exactly one atom
+fn consensus(c1: &Clause, c2: &Clause) -> Option<Clause> {
+    let mut clashes = c1.iter().filter(|(atom, positive)| c2.contains(&(atom.clone(), !positive)));
+    let (atom, _) = clashes.next()?;
+    if clashes.next().is_some() {
+        return None;
+    }
+    Some(c1.union(c2).filter(|(a, _)| a != atom).cloned().collect())
+}
+
+// Removes every clause that contains another clause
+fn absorb(clauses: BTreeSet<Clause>) -> BTreeSet<Clause> {
+    clauses
+        .iter()
+        .filter(|c| !clauses.iter().any(|other| other != *c && other.is_subset(c)))
+        .cloned()
+        .collect()
+}
+
+// Folds sorted clauses with `outer`, and the literals of each with `inner`.
+// `dnf` tells whether an empty clause is true and no clauses false, or the reverse.
+fn from_clauses(
+    clauses: &BTreeSet<Clause>,
+    outer: fn(Box<LabelType>, Box<LabelType>) -> LabelType,
+    inner: fn(Box<LabelType>, Box<LabelType>) -> LabelType,
+    dnf: bool,
+) -> LabelType {
+    let (unit, zero) = if dnf {
+        (LabelType::Star, LabelType::Not(Box::new(LabelType::Star)))
+    } else {
+        (LabelType::Not(Box::new(LabelType::Star)), LabelType::Star)
+    };
+    clauses
+        .iter()
+        .map(|clause| {
+            clause
+                .iter()
+                .map(literal)
+                .reduce(|l, r| inner(Box::new(l), Box::new(r)))
+                .unwrap_or_else(|| unit.clone())
+        })
+        .reduce(|l, r| outer(Box::new(l), Box::new(r)))
+        .unwrap_or(zero)
+}
+
+fn literal((atom, positive): &Literal) -> LabelType {
+    let label = match atom {
+        Atom::Wildcard => LabelType::Wildcard,
+        Atom::Label(s) => LabelType::Label(s.clone()),
+    };
+    if *positive { label } else { LabelType::Not(Box::new(label)) }
+}
@@ -1,17 +1,191 @@
 use std::fmt;
 use super::descriptor::Descriptor;
 use super::expr::Expr;
+use super::var::Var;
 
 pub enum PathPattern {
     Node(NodePattern),
+    Edge(EdgePattern),
     Filter(Box<PathPattern>, Expr),
+    Concat(Box<PathPattern>, Box<PathPattern>),        // e.g. (x)-[e]->(y), left-nested
+    Union(Box<PathPattern>, Box<PathPattern>),         // e.g. (x:Person) | (x:Company)
+    MultisetUnion(Box<PathPattern>, Box<PathPattern>), // e.g. (x) |+| (x), keeps duplicates
+    Repeat {
+        pattern: Box<PathPattern>,
+        min: u32,
+        max: Option<u32>, // None means unbounded, e.g. * or {2,}
+    },
+    Optional(Box<PathPattern>),               // e.g. -[z]->?, may match nothing
+    Named(Var, Box<PathPattern>),             // e.g. p = (x)-[e]->(y), binds the whole path to p
+    Restricted(Restrictor, Box<PathPattern>), // e.g. TRAIL (x)-[e]->*(y)
+    Selected(Selector, Box<PathPattern>),     // e.g. ANY SHORTEST (x)-[e]->*(y), top-level only
+}
+
+impl PathPattern {
+    /// Variables bound anywhere in the pattern, in order of first occurrence.
+    pub fn variables(&self) -> Vec<&Var> {
+        let mut vars = Vec::new();
+        self.collect_variables(false, &mut vars);
+        let mut unique: Vec<&Var> = Vec::new();
+        for (var, _) in vars {
+            if !unique.contains(&var) {
+                unique.push(var);
+            }
+        }
+        unique
+    }
+
+    /// Variables bound only inside optional segments. A match may leave
+    /// them unbound, so later passes must not assume they have a value.
+    pub fn optional_variables(&self) -> Vec<&Var> {
+        let mut vars = Vec::new();
+        self.collect_variables(false, &mut vars);
+        let mut optional: Vec<&Var> = Vec::new();
+        for (var, _) in vars.iter().filter(|(_, in_optional)| *in_optional) {
+            let always_bound = vars.iter().any(|(v, in_optional)| v == var && !in_optional);
+            if !always_bound && !optional.contains(var) {
+                optional.push(var);
+            }
+        }
+        optional
+    }
+
+    /// The selector in front of this pattern, looking through a path variable binding.
+    pub fn selector(&self) -> Option<Selector> {
+        match self {
+            PathPattern::Selected(s, _) => Some(*s),
+            PathPattern::Named(_, p) => p.selector(),
+            _ => None,
+        }
+    }
+
+    /// Variables bound to whole paths (`p = ...`), in order of first occurrence.
+    pub fn path_variables(&self) -> Vec<&Var> {
+        match self {
+            PathPattern::Node(_) | PathPattern::Edge(_) => Vec::new(),
+            PathPattern::Filter(p, _)
+            | PathPattern::Repeat { pattern: p, .. }
+            | PathPattern::Optional(p)
+            | PathPattern::Restricted(_, p)
+            | PathPattern::Selected(_, p) => p.path_variables(),
+            PathPattern::Concat(p1, p2)
+            | PathPattern::Union(p1, p2)
+            | PathPattern::MultisetUnion(p1, p2) => {
+                let mut vars = p1.path_variables();
+                for var in p2.path_variables() {
+                    if !vars.contains(&var) {
+                        vars.push(var);
+                    }
+                }
+                vars
+            }
+            PathPattern::Named(v, p) => {
+                let mut vars = vec![v];
+                vars.extend(p.path_variables().into_iter().filter(|inner| *inner != v));
+                vars
+            }
+        }
+    }
+
+    fn collect_variables<'a>(&'a self, in_optional: bool, vars: &mut Vec<(&'a Var, bool)>) {
+        match self {
+            PathPattern::Node(n) => vars.extend(n.descriptor.variable.iter().map(|v| (v, in_optional))),
+            PathPattern::Edge(e) => vars.extend(e.descriptor.variable.iter().map(|v| (v, in_optional))),
+            PathPattern::Filter(p, _) => p.collect_variables(in_optional, vars),
+            PathPattern::Concat(p1, p2)
+            | PathPattern::Union(p1, p2)
+            | PathPattern::MultisetUnion(p1, p2) => {
+                p1.collect_variables(in_optional, vars);
+                p2.collect_variables(in_optional, vars);
+            }
+            PathPattern::Repeat { pattern, .. } => pattern.collect_variables(in_optional, vars),
+            PathPattern::Optional(p) => p.collect_variables(true, vars),
+            PathPattern::Named(v, p) => {
+                vars.push((v, in_optional));
+                p.collect_variables(in_optional, vars);
+            }
+            PathPattern::Restricted(_, p) | PathPattern::Selected(_, p) => {
+                p.collect_variables(in_optional, vars)
+            }
+        }
+    }
 }
 
 impl fmt::Debug for PathPattern {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             PathPattern::Node(n) => write!(f, "{:?}", n),
+            PathPattern::Edge(e) => write!(f, "{:?}", e),
             PathPattern::Filter(p, e) => write!(f, "({:?} WHERE {:?})", p, e),
+            PathPattern::Concat(p1, p2) => write!(f, "{:?} {:?}", p1, p2),
+            PathPattern::Union(p1, p2) => write!(f, "({:?} | {:?})", p1, p2),
+            PathPattern::MultisetUnion(p1, p2) => write!(f, "({:?} |+| {:?})", p1, p2),
+            PathPattern::Repeat { pattern, min, max } => {
+                fmt_quantified(pattern, f)?;
+                match max {
+                    Some(max) => write!(f, "{{{},{}}}", min, max),
+                    None => write!(f, "{{{},}}", min),
+                }
+            }
+            PathPattern::Optional(p) => {
+                fmt_quantified(p, f)?;
+                write!(f, "?")
+            }
+            PathPattern::Named(v, p) => write!(f, "{} = {:?}", v.0, p),
+            PathPattern::Restricted(r, p) => write!(f, "{} {:?}", r, p),
+            PathPattern::Selected(s, p) => write!(f, "{} {:?}", s, p),
+        }
+    }
+}
+
+// A quantified or optional concatenation needs parentheses: ((x) (y)){1,3}
+// is not (x) (y){1,3}, nor is ((x) (y))? the same as (x) (y)?
+fn fmt_quantified(pattern: &PathPattern, f: &mut fmt::Formatter) -> fmt::Result {
+    match pattern {
+        PathPattern::Concat(_, _) => write!(f, "({:?})", pattern),
+        _ => write!(f, "{:?}", pattern),
+    }
+}
+
+/// Path mode restricting which paths a pattern may match, so that
+/// unbounded repetition only yields finitely many results.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Restrictor {
+    Walk,    // no restriction
+    Trail,   // no repeated edges
+    Simple,  // no repeated nodes, except first = last
+    Acyclic, // no repeated nodes
+}
+
+impl fmt::Display for Restrictor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Restrictor::Walk => write!(f, "WALK"),
+            Restrictor::Trail => write!(f, "TRAIL"),
+            Restrictor::Simple => write!(f, "SIMPLE"),
+            Restrictor::Acyclic => write!(f, "ACYCLIC"),
+        }
+    }
+}
+
+/// Selector choosing a finite subset of the matched paths per endpoint pair.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Selector {
+    Any(u32),            // ANY or ANY k, k arbitrary paths
+    AnyShortest,         // ANY SHORTEST, one shortest path
+    AllShortest,         // ALL SHORTEST, every shortest path
+    Shortest(u32),       // SHORTEST k, the k shortest paths
+    ShortestGroups(u32), // SHORTEST k GROUPS, all paths in the k shortest lengths
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Selector::Any(k) => write!(f, "ANY {}", k),
+            Selector::AnyShortest => write!(f, "ANY SHORTEST"),
+            Selector::AllShortest => write!(f, "ALL SHORTEST"),
+            Selector::Shortest(k) => write!(f, "SHORTEST {}", k),
+            Selector::ShortestGroups(k) => write!(f, "SHORTEST {} GROUPS", k),
         }
     }
 }
@@ -25,3 +199,34 @@ impl fmt::Debug for NodePattern {
         write!(f, "({:?})", self.descriptor)
     }
 }
+
+/// Orientation of an edge pattern relative to the order in which it is written.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EdgeDirection {
+    Left,              // <-[]- or <-
+    Undirected,        // ~[]~ or ~
+    Right,             // -[]-> or ->
+    LeftOrUndirected,  // <~[]~ or <~
+    RightOrUndirected, // ~[]~> or ~>
+    LeftOrRight,       // <-[]-> or <->
+    Any,               // -[]- or -
+}
+
+pub struct EdgePattern {
+    pub descriptor: Descriptor,
+    pub direction: EdgeDirection,
+}
+
+impl fmt::Debug for EdgePattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.direction {
+            EdgeDirection::Left => write!(f, "<-[{:?}]-", self.descriptor),
+            EdgeDirection::Undirected => write!(f, "~[{:?}]~", self.descriptor),
+            EdgeDirection::Right => write!(f, "-[{:?}]->", self.descriptor),
+            EdgeDirection::LeftOrUndirected => write!(f, "<~[{:?}]~", self.descriptor),
+            EdgeDirection::RightOrUndirected => write!(f, "~[{:?}]~>", self.descriptor),
+            EdgeDirection::LeftOrRight => write!(f, "<-[{:?}]->", self.descriptor),
+            EdgeDirection::Any => write!(f, "-[{:?}]-", self.descriptor),
+        }
+    }
+}
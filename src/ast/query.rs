@@ -0,0 +1,40 @@
+use std::fmt;
+use super::expr::Expr;
+use super::graph::GraphPattern;
+use super::var::Var;
+
+/// A complete query `MATCH <graph pattern> [WHERE <expr>] RETURN <items>`.
+/// The optional WHERE is kept on the graph pattern itself.
+pub struct Query {
+    pub pattern: GraphPattern,
+    pub items: Vec<ReturnItem>,
+}
+
+impl fmt::Debug for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MATCH {:?} RETURN ", self.pattern)?;
+        for (i, item) in self.items.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:?}", item)?;
+        }
+        Ok(())
+    }
+}
+
+/// A single projected expression, e.g. `x.name AS name`.
+#[derive(PartialEq, Clone)]
+pub struct ReturnItem {
+    pub expr: Expr,
+    pub alias: Option<Var>,
+}
+
+impl fmt::Debug for ReturnItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.alias {
+            Some(alias) => write!(f, "{:?} AS {}", self.expr, alias.0),
+            None => write!(f, "{:?}", self.expr),
+        }
+    }
+}
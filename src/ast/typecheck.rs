@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::fmt;
+use super::descriptor::DescriptorType;
+use super::expr::{BinOpKind, Constant, Expr, UnOpKind};
+use super::types::{BaseType, PropertyType, Record, SimpleType};
+use super::var::Var;
+
+/// Reason an expression is ill-typed, naming the offending sub-expression.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TypeError {
+    /// A variable missing from the environment
+    UnboundVariable(Var),
+    /// An operand whose type does not fit the operator, e.g. `true` in `1 + true`
+    Mismatch { expr: Box<Expr>, expected: Box<SimpleType>, found: Box<SimpleType> },
+    /// A comparison between unrelated types, e.g. `x.a > 'b'` with `a: int`
+    Incomparable { expr: Box<Expr>, left: Box<SimpleType>, right: Box<SimpleType> },
+    /// A lookup of a key that a closed record does not have
+    NoSuchKey { expr: Box<Expr>, key: String },
+    /// A right operand of IS or AS that is not a type
+    NotATypeLiteral(Box<Expr>),
+    /// A type used as a value, outside IS or AS
+    UnexpectedTypeLiteral(Box<SimpleType>),
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TypeError::UnboundVariable(v) => write!(f, "variable `{}` is not bound", v.0),
+            TypeError::Mismatch { expr, expected, found } => {
+                write!(f, "`{}` has type {}, expected {}", expr, found, expected)
+            }
+            TypeError::Incomparable { expr, left, right } => {
+                write!(f, "`{}` compares unrelated types {} and {}", expr, left, right)
+            }
+            TypeError::NoSuchKey { expr, key } => {
+                write!(f, "`{}` looks up `{}`, which the closed record does not have", expr, key)
+            }
+            TypeError::NotATypeLiteral(expr) => write!(f, "`{}` is not a type, expected after IS or AS", expr),
+            TypeError::UnexpectedTypeLiteral(ty) => write!(f, "type {} is only allowed after IS or AS", ty),
+        }
+    }
+}
+
+impl Expr {
+    /// Static type of the expression, where each variable has the property
+    /// record of its descriptor type in `env`. `*` is the dynamic type: it
+    /// fits every operator, and lookups on an open record give `*` for keys
+    /// it does not list. Optional keys and nullable operands give nullable
+    /// results; comparisons and logical operators always give `bool`.
+    pub fn type_of(&self, env: &HashMap<Var, DescriptorType>) -> Result<SimpleType, TypeError> {
+        match self {
+            Expr::Constant(c) => Ok(constant_type(c)),
+            Expr::Variable(v) => env
+                .get(v)
+                .map(|desc| SimpleType::Record(desc.properties.clone()))
+                .ok_or_else(|| TypeError::UnboundVariable(v.clone())),
+            Expr::TypeLiteral(t) => Err(TypeError::UnexpectedTypeLiteral(Box::new(t.clone()))),
+            Expr::AttributeLookup(lookup) => {
+                let (record, nullable) = match lookup.e.type_of(env)? {
+                    SimpleType::Star => return Ok(SimpleType::Star),
+                    SimpleType::Record(p) => (p, false),
+                    SimpleType::Nullable(t) => match *t {
+                        SimpleType::Record(p) => (p, true),
+                        t => return Err(mismatch(&lookup.e, any_record(), make_nullable(t))),
+                    },
+                    found => return Err(mismatch(&lookup.e, any_record(), found)),
+                };
+                let ty = match record.record().field(&lookup.a.0) {
+                    Some(field) if field.optional || nullable => make_nullable(field.ty.clone()),
+                    Some(field) => field.ty.clone(),
+                    None if record.is_closed() => {
+                        return Err(TypeError::NoSuchKey { expr: Box::new(self.clone()), key: lookup.a.0.clone() });
+                    }
+                    None => SimpleType::Star,
+                };
+                Ok(ty)
+            }
+            Expr::Unop(u) => {
+                let ty = u.e.type_of(env)?;
+                match u.op {
+                    UnOpKind::Neg => {
+                        expect(&u.e, &ty, &numeric())?;
+                        Ok(ty)
+                    }
+                    UnOpKind::Not => {
+                        expect(&u.e, &ty, &boolean())?;
+                        Ok(boolean())
+                    }
+                }
+            }
+            Expr::Binop(b) => match b.op {
+                BinOpKind::Is => {
+                    b.e1.type_of(env)?;
+                    match &*b.e2 {
+                        Expr::TypeLiteral(_) | Expr::Constant(Constant::Null) => Ok(boolean()),
+                        e2 => Err(TypeError::NotATypeLiteral(Box::new(e2.clone()))),
+                    }
+                }
+                BinOpKind::As => {
+                    b.e1.type_of(env)?;
+                    match &*b.e2 {
+                        Expr::TypeLiteral(t) => Ok(t.clone()),
+                        e2 => Err(TypeError::NotATypeLiteral(Box::new(e2.clone()))),
+                    }
+                }
+                BinOpKind::Add | BinOpKind::Sub | BinOpKind::Mul | BinOpKind::Div => {
+                    let (t1, t2) = (b.e1.type_of(env)?, b.e2.type_of(env)?);
+                    expect(&b.e1, &t1, &numeric())?;
+                    expect(&b.e2, &t2, &numeric())?;
+                    let int = make_nullable(SimpleType::Base(BaseType::Int));
+                    let ty = if t1.is_subtype_of(&int) && t2.is_subtype_of(&int) {
+                        SimpleType::Base(BaseType::Int)
+                    } else if t1 == SimpleType::Star || t2 == SimpleType::Star {
+                        return Ok(SimpleType::Star);
+                    } else {
+                        SimpleType::Base(BaseType::Float)
+                    };
+                    Ok(if admits_null(&t1) || admits_null(&t2) { make_nullable(ty) } else { ty })
+                }
+                BinOpKind::Lt | BinOpKind::Gt | BinOpKind::Le | BinOpKind::Ge => {
+                    let (t1, t2) = (b.e1.type_of(env)?, b.e2.type_of(env)?);
+                    expect(&b.e1, &t1, &ordered())?;
+                    expect(&b.e2, &t2, &ordered())?;
+                    comparable(self, t1, t2)
+                }
+                BinOpKind::Eq | BinOpKind::Ne => {
+                    let (t1, t2) = (b.e1.type_of(env)?, b.e2.type_of(env)?);
+                    comparable(self, t1, t2)
+                }
+                BinOpKind::And | BinOpKind::Or => {
+                    let (t1, t2) = (b.e1.type_of(env)?, b.e2.type_of(env)?);
+                    expect(&b.e1, &t1, &boolean())?;
+                    expect(&b.e2, &t2, &boolean())?;
+                    Ok(boolean())
+                }
+            },
+            // Elements widen to a common supertype, falling back to a union
+            Expr::List(items) => {
+                let mut elem: Option<SimpleType> = None;
+                for item in items {
+                    let ty = item.type_of(env)?;
+                    elem = Some(match elem {
+                        None => ty,
+                        Some(acc) if ty.is_subtype_of(&acc) => acc,
+                        Some(acc) if acc.is_subtype_of(&ty) => ty,
+                        Some(acc) => SimpleType::Union(Box::new(acc), Box::new(ty)),
+                    });
+                }
+                Ok(SimpleType::List(Box::new(elem.unwrap_or(SimpleType::Star))))
+            }
+        }
+    }
+}
+
+fn constant_type(c: &Constant) -> SimpleType {
+    SimpleType::Base(match c {
+        Constant::String(_) => BaseType::String,
+        Constant::Int(_) => BaseType::Int,
+        Constant::Bool(_) => BaseType::Bool,
+        Constant::Float(_) => BaseType::Float,
+        Constant::Date(_) => BaseType::Date,
+        Constant::DateTime(_) => BaseType::DateTime,
+        Constant::Duration(_) => BaseType::Duration,
+        Constant::Null => BaseType::Null,
+    })
+}
+
+// Equal or ordered operands must have related types; `NULL` compares with anything
+fn comparable(expr: &Expr, t1: SimpleType, t2: SimpleType) -> Result<SimpleType, TypeError> {
+    let null = SimpleType::Base(BaseType::Null);
+    let related = t1.is_subtype_of(&t2)
+        || t2.is_subtype_of(&t1)
+        || (t1.is_subtype_of(&numeric()) && t2.is_subtype_of(&numeric()))
+        || t1 == null
+        || t2 == null;
+    if related {
+        Ok(boolean())
+    } else {
+        Err(TypeError::Incomparable { expr: Box::new(expr.clone()), left: Box::new(t1), right: Box::new(t2) })
+    }
+}
+
+// `*` and null fit every operand position
+fn expect(expr: &Expr, found: &SimpleType, expected: &SimpleType) -> Result<(), TypeError> {
+    if *found == SimpleType::Star || found.is_subtype_of(&make_nullable(expected.clone())) {
+        Ok(())
+    } else {
+        Err(mismatch(expr, expected.clone(), found.clone()))
+    }
+}
+
+fn mismatch(expr: &Expr, expected: SimpleType, found: SimpleType) -> TypeError {
+    TypeError::Mismatch { expr: Box::new(expr.clone()), expected: Box::new(expected), found: Box::new(found) }
+}
+
+fn admits_null(ty: &SimpleType) -> bool {
+    *ty != SimpleType::Star && SimpleType::Base(BaseType::Null).is_subtype_of(ty)
+}
+
+fn make_nullable(ty: SimpleType) -> SimpleType {
+    if ty == SimpleType::Star || admits_null(&ty) { ty } else { SimpleType::Nullable(Box::new(ty)) }
+}
+
+fn boolean() -> SimpleType {
+    SimpleType::Base(BaseType::Bool)
+}
+
+fn numeric() -> SimpleType {
+    union(&[BaseType::Int, BaseType::Float])
+}
+
+fn ordered() -> SimpleType {
+    union(&[BaseType::Int, BaseType::Float, BaseType::String, BaseType::Date, BaseType::DateTime, BaseType::Duration])
+}
+
+fn any_record() -> SimpleType {
+    SimpleType::Record(PropertyType::Open(Record::new()))
+}
+
+fn union(bases: &[BaseType]) -> SimpleType {
+    bases
+        .iter()
+        .map(|b| SimpleType::Base(b.clone()))
+        .reduce(|l, r| SimpleType::Union(Box::new(l), Box::new(r)))
+        .unwrap()
+}
@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::fmt;
 
 #[derive(PartialEq, Clone)]
@@ -6,6 +5,11 @@ pub enum BaseType {
     Int,
     Bool,
     String,
+    Float,
+    Date,
+    DateTime,
+    Duration,
+    Null,
 }
 
 impl fmt::Debug for BaseType {
@@ -14,6 +18,11 @@ impl fmt::Debug for BaseType {
             BaseType::Int => write!(f, "int"),
             BaseType::Bool => write!(f, "bool"),
             BaseType::String => write!(f, "str"),
+            BaseType::Float => write!(f, "float"),
+            BaseType::Date => write!(f, "date"),
+            BaseType::DateTime => write!(f, "datetime"),
+            BaseType::Duration => write!(f, "duration"),
+            BaseType::Null => write!(f, "null"),
         }
     }
 }
@@ -24,6 +33,11 @@ impl fmt::Display for BaseType {
             BaseType::Int => write!(f, "int"),
             BaseType::Bool => write!(f, "bool"),
             BaseType::String => write!(f, "str"),
+            BaseType::Float => write!(f, "float"),
+            BaseType::Date => write!(f, "date"),
+            BaseType::DateTime => write!(f, "datetime"),
+            BaseType::Duration => write!(f, "duration"),
+            BaseType::Null => write!(f, "null"),
         }
     }
 }
@@ -32,6 +46,10 @@ impl fmt::Display for BaseType {
 pub enum SimpleType {
     Base(BaseType),
     Star,
+    Nullable(Box<SimpleType>),              // e.g. int?, an int or null
+    Union(Box<SimpleType>, Box<SimpleType>), // e.g. int | str
+    List(Box<SimpleType>),                   // e.g. list<str>
+    Record(PropertyType),                    // e.g. {city: str}, a nested record
 }
 
 impl fmt::Debug for SimpleType {
@@ -39,6 +57,12 @@ impl fmt::Debug for SimpleType {
         match self {
             SimpleType::Base(b) => write!(f, "{:?}", b),
             SimpleType::Star => write!(f, "*"),
+            // Unions have no postfix form: (int | str)? is written int | str | null
+            SimpleType::Nullable(t) if matches!(**t, SimpleType::Union(_, _)) => write!(f, "{:?} | null", t),
+            SimpleType::Nullable(t) => write!(f, "{:?}?", t),
+            SimpleType::Union(t1, t2) => write!(f, "{:?} | {:?}", t1, t2),
+            SimpleType::List(t) => write!(f, "list<{:?}>", t),
+            SimpleType::Record(p) => write!(f, "{:?}", p),
         }
     }
 }
@@ -48,14 +72,133 @@ impl fmt::Display for SimpleType {
         match self {
             SimpleType::Base(b) => write!(f, "{}", b),
             SimpleType::Star => write!(f, "*"),
+            SimpleType::Nullable(t) if matches!(**t, SimpleType::Union(_, _)) => write!(f, "{} | null", t),
+            SimpleType::Nullable(t) => write!(f, "{}?", t),
+            SimpleType::Union(t1, t2) => write!(f, "{} | {}", t1, t2),
+            SimpleType::List(t) => write!(f, "list<{}>", t),
+            SimpleType::Record(p) => write!(f, "{}", p),
         }
     }
 }
 
-#[derive(Clone)]
+impl SimpleType {
+    /// Whether every value of `self` is also a value of `other`.
+    /// `*` is the top type, `t?` is `t | null`, lists are covariant and
+    /// nested records follow `PropertyType::is_subtype_of`.
+    pub fn is_subtype_of(&self, other: &SimpleType) -> bool {
+        match (self, other) {
+            // Decompose the left side first, so that e.g. int? fits int | null
+            (SimpleType::Union(s1, s2), _) => s1.is_subtype_of(other) && s2.is_subtype_of(other),
+            (SimpleType::Nullable(s), _) => {
+                s.is_subtype_of(other) && SimpleType::Base(BaseType::Null).is_subtype_of(other)
+            }
+            (_, SimpleType::Star) => true,
+            (_, SimpleType::Union(t1, t2)) => self.is_subtype_of(t1) || self.is_subtype_of(t2),
+            (_, SimpleType::Nullable(t)) => {
+                self.is_subtype_of(t) || *self == SimpleType::Base(BaseType::Null)
+            }
+            (SimpleType::Base(a), SimpleType::Base(b)) => a == b,
+            (SimpleType::List(s), SimpleType::List(t)) => s.is_subtype_of(t),
+            (SimpleType::Record(s), SimpleType::Record(t)) => s.is_subtype_of(t).is_ok(),
+            _ => false,
+        }
+    }
+}
+
+/// Type of a single record key.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RecordField {
+    pub ty: SimpleType,
+    pub optional: bool, // e.g. age?: int, the key may be missing
+}
+
+/// Keys of a record type and the type of each key, in source order.
+/// Equality ignores the order: `{a: int, b: str}` equals `{b: str, a: int}`.
+#[derive(Debug, Default, Clone)]
+pub struct Record {
+    fields: Vec<(String, RecordField)>,
+}
+
+impl Record {
+    pub fn new() -> Self {
+        Record::default()
+    }
+
+    /// Adds the key at the end, or replaces its type in place if already present.
+    pub fn insert(&mut self, key: String, field: RecordField) {
+        match self.fields.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => *existing = field,
+            None => self.fields.push((key, field)),
+        }
+    }
+
+    /// Type of the key, whether or not the key is optional.
+    pub fn get(&self, key: &str) -> Option<&SimpleType> {
+        self.field(key).map(|field| &field.ty)
+    }
+
+    pub fn field(&self, key: &str) -> Option<&RecordField> {
+        self.fields.iter().find(|(k, _)| k == key).map(|(_, field)| field)
+    }
+
+    pub fn is_optional(&self, key: &str) -> bool {
+        self.field(key).is_some_and(|field| field.optional)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.field(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &RecordField)> {
+        self.fields.iter().map(|(key, field)| (key, field))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.fields.iter().map(|(key, _)| key)
+    }
+
+    // Shared by Debug and Display, which differ only in how types are written
+    fn fmt_fields(
+        &self,
+        f: &mut fmt::Formatter,
+        fmt_type: fn(&SimpleType, &mut fmt::Formatter) -> fmt::Result,
+    ) -> fmt::Result {
+        for (i, (key, field)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}{}: ", key, if field.optional { "?" } else { "" })?;
+            fmt_type(&field.ty, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for Record {
+    fn eq(&self, other: &Record) -> bool {
+        self.len() == other.len() && self.iter().all(|(key, field)| other.field(key) == Some(field))
+    }
+}
+
+// Display - the fields as written in source, e.g. "a: int, b?: str"
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_fields(f, fmt::Display::fmt)
+    }
+}
+
+#[derive(PartialEq, Clone)]
 pub enum PropertyType {
-    Open(HashMap<String, SimpleType>),
-    Closed(HashMap<String, SimpleType>),
+    Open(Record),
+    Closed(Record),
 }
 
 impl fmt::Debug for PropertyType {
@@ -65,15 +208,8 @@ impl fmt::Debug for PropertyType {
                 if map.is_empty() {
                     write!(f, "{{*}}")
                 } else {
-                    let mut keys: Vec<_> = map.keys().collect();
-                    keys.sort();
                     write!(f, "{{")?;
-                    for (i, key) in keys.iter().enumerate() {
-                        if i > 0 {
-                            write!(f, ", ")?;
-                        }
-                        write!(f, "{}: {:?}", key, map[*key])?;
-                    }
+                    map.fmt_fields(f, fmt::Debug::fmt)?;
                     write!(f, ", *}}")
                 }
             }
@@ -81,15 +217,8 @@ impl fmt::Debug for PropertyType {
                 if map.is_empty() {
                     write!(f, "{{*}}")
                 } else {
-                    let mut keys: Vec<_> = map.keys().collect();
-                    keys.sort();
                     write!(f, "{{")?;
-                    for (i, key) in keys.iter().enumerate() {
-                        if i > 0 {
-                            write!(f, ", ")?;
-                        }
-                        write!(f, "{}: {:?}", key, map[*key])?;
-                    }
+                    map.fmt_fields(f, fmt::Debug::fmt)?;
                     write!(f, "}}")
                 }
             }
@@ -97,3 +226,76 @@ impl fmt::Debug for PropertyType {
     }
 }
 
+// Display - re-parseable form, e.g. {a: int} or {{a: int}}
+impl fmt::Display for PropertyType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PropertyType::Open(map) => write!(f, "{{{}}}", map),
+            PropertyType::Closed(map) => write!(f, "{{{{{}}}}}", map),
+        }
+    }
+}
+
+/// Reason a `PropertyType` is not a subtype of another.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SubtypeError {
+    /// The key is missing, only optional, not allowed, or of an incompatible type
+    Key(String),
+    /// An open record may carry keys that the closed record does not allow
+    Open,
+}
+
+impl fmt::Display for SubtypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SubtypeError::Key(key) => write!(f, "property `{}` does not fit", key),
+            SubtypeError::Open => write!(f, "an open record does not fit a closed record"),
+        }
+    }
+}
+
+impl PropertyType {
+    pub fn record(&self) -> &Record {
+        match self {
+            PropertyType::Open(map) | PropertyType::Closed(map) => map,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        matches!(self, PropertyType::Closed(_))
+    }
+
+    /// Width and depth subtyping: `self` fits `other` when every record
+    /// described by `self` is also described by `other`.
+    /// - a required key of `other` must be required in `self`, with a subtype
+    /// - an optional key of `other` may be absent from a closed `self`; an
+    ///   open `self` may carry it with any type, so it must then be `*`
+    /// - a closed `other` only accepts a closed `self` without extra keys
+    ///
+    /// Returns the first offending key of `other`, then of `self`, in source order.
+    pub fn is_subtype_of(&self, other: &PropertyType) -> Result<(), SubtypeError> {
+        let (sub, sup) = (self.record(), other.record());
+        for (key, expected) in sup.iter() {
+            let fits = match sub.field(key) {
+                Some(actual) => {
+                    (expected.optional || !actual.optional) && actual.ty.is_subtype_of(&expected.ty)
+                }
+                None if expected.optional && self.is_closed() => true,
+                None if expected.optional => expected.ty == SimpleType::Star,
+                None => false,
+            };
+            if !fits {
+                return Err(SubtypeError::Key(key.clone()));
+            }
+        }
+        if other.is_closed() {
+            if let Some(key) = sub.keys().find(|key| !sup.contains_key(key)) {
+                return Err(SubtypeError::Key(key.clone()));
+            }
+            if !self.is_closed() {
+                return Err(SubtypeError::Open);
+            }
+        }
+        Ok(())
+    }
+}
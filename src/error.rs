@@ -0,0 +1,68 @@
+use std::fmt;
+use crate::ast::Selector;
+
+/// Errors raised by grammar actions once a production has matched
+/// syntactically but violates a semantic rule of the language.
+/// Surfaced as `ParseError::User` by the generated parsers.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SyntaxError {
+    /// A repetition `{n,m}` whose lower bound exceeds its upper bound
+    InvalidRepetition { min: u32, max: u32 },
+    /// A repetition bound that does not fit in a `u32`
+    RepetitionBoundOutOfRange(i64),
+    /// An integer literal that does not fit in an `i64`
+    IntegerOutOfRange(String),
+    /// A path selector such as `ANY SHORTEST` inside a parenthesised subpattern
+    NestedSelector(Selector),
+    /// Property values such as `{name: 'Alice'}` on an element without a variable
+    AnonymousPropertyValues,
+    /// A key given twice in one record, with the byte offsets of both occurrences
+    DuplicateKey { key: String, first: usize, second: usize },
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SyntaxError::InvalidRepetition { min, max } => write!(
+                f,
+                "invalid repetition {{{},{}}}: lower bound {} exceeds upper bound {}",
+                min, max, min, max
+            ),
+            SyntaxError::RepetitionBoundOutOfRange(n) => {
+                write!(f, "repetition bound {} is out of range", n)
+            }
+            SyntaxError::IntegerOutOfRange(digits) => {
+                write!(f, "integer literal {} is out of range", digits)
+            }
+            SyntaxError::NestedSelector(selector) => write!(
+                f,
+                "path selector {} is only allowed on a top-level path pattern",
+                selector
+            ),
+            SyntaxError::AnonymousPropertyValues => write!(
+                f,
+                "property values require an element variable, e.g. (x:Person {{name: 'Alice'}})"
+            ),
+            SyntaxError::DuplicateKey { key, first, second } => write!(
+                f,
+                "duplicate key `{}` at position {}, first given at position {}",
+                key, second, first
+            ),
+        }
+    }
+}
+
+/// Keys of a record literal seen so far, with their byte offsets,
+/// so that a repeated key can be reported with both occurrences.
+#[derive(Debug, Default)]
+pub(crate) struct KeyPositions(Vec<(String, usize)>);
+
+impl KeyPositions {
+    pub(crate) fn add(&mut self, key: &str, position: usize) -> Result<(), SyntaxError> {
+        if let Some((_, first)) = self.0.iter().find(|(k, _)| k == key) {
+            return Err(SyntaxError::DuplicateKey { key: key.to_string(), first: *first, second: position });
+        }
+        self.0.push((key.to_string(), position));
+        Ok(())
+    }
+}
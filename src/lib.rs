@@ -1,19 +1,27 @@
 use lalrpop_util::lalrpop_mod;
 
 pub mod ast;
+pub mod error;
 
 lalrpop_mod!(pub grammar);
 
 pub use crate::grammar::{
     LabelTypeParser, SimpleTypeParser, PropertyTypeParser, 
     DescriptorTypeParser, DescriptorParser, PathPatternParser,
-    ExprParser
+    GraphPatternParser, QueryParser, ExprParser
 };
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ast::{Var, LabelType, PropertyType, SimpleType, BaseType, Expr, Constant, BinOpKind, UnOpKind};
+    use ast::{Var, LabelType, PropertyType, SimpleType, BaseType, Expr, Constant, BinOpKind, UnOpKind, EdgeDirection, Restrictor, Selector};
+
+    // Parses `input` with the named parser, panicking on a parse error
+    macro_rules! parse {
+        ($parser:ident, $input:expr) => {
+            $parser::new().parse($input).unwrap()
+        };
+    }
 
     // ==========================================
     // NODE PATTERN TESTS (from parser_test.py)
@@ -162,6 +170,189 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_record_optional_and_nullable() {
+        // (x :Person {{name: str, age?: int, nickname: str?}})
+        let result = PathPatternParser::new()
+            .parse("(x :Person {{name: str, age?: int, nickname: str?}})")
+            .unwrap();
+        let node = match result {
+            ast::PathPattern::Node(n) => n,
+            _ => panic!("Expected PathPattern::Node"),
+        };
+        match &node.descriptor.descriptor_type.properties {
+            PropertyType::Closed(map) => {
+                assert_eq!(map.len(), 3);
+                assert!(!map.is_optional("name"));
+                assert!(map.is_optional("age"));
+                assert!(matches!(map.get("age"), Some(SimpleType::Base(BaseType::Int))));
+                assert!(!map.is_optional("nickname"));
+                assert_eq!(
+                    map.get("nickname"),
+                    Some(&SimpleType::Nullable(Box::new(SimpleType::Base(BaseType::String))))
+                );
+            }
+            _ => panic!("Expected Closed properties"),
+        }
+        assert_eq!(
+            format!("{:?}", node.descriptor.descriptor_type.properties),
+            "{name: str, age?: int, nickname: str?}"
+        );
+    }
+
+    #[test]
+    fn test_record_duplicate_key() {
+        // {a: int, a: str}: both occurrences are reported by byte offset
+        let duplicate = |first, second| error::SyntaxError::DuplicateKey { key: "a".to_string(), first, second };
+        match PropertyTypeParser::new().parse("{a: int, a: str}") {
+            Err(lalrpop_util::ParseError::User { error }) => assert_eq!(error, duplicate(1, 9)),
+            _ => panic!("Expected SyntaxError::DuplicateKey"),
+        }
+        match PropertyTypeParser::new().parse("{{a: int, b: {a: str}, a?: int}}") {
+            Err(lalrpop_util::ParseError::User { error }) => assert_eq!(error, duplicate(2, 23)),
+            _ => panic!("Expected SyntaxError::DuplicateKey"),
+        }
+        // Typed keys and pinned values share one key space
+        match PathPatternParser::new().parse("(x:Person {a: str, a: 'Alice'})") {
+            Err(lalrpop_util::ParseError::User { error }) => assert_eq!(error, duplicate(11, 19)),
+            _ => panic!("Expected SyntaxError::DuplicateKey"),
+        }
+        // The same key in a nested record is not a duplicate
+        assert!(PropertyTypeParser::new().parse("{a: {a: int}}").is_ok());
+    }
+
+    #[test]
+    fn test_record_source_order() {
+        let record = parse!(PropertyTypeParser, "{c: int, a: str, b?: bool}");
+        assert_eq!(record.record().keys().collect::<Vec<_>>(), ["c", "a", "b"]);
+        assert_eq!(format!("{}", record), "{c: int, a: str, b?: bool}");
+        assert_eq!(record, parse!(PropertyTypeParser, "{a: str, b?: bool, c: int}"));
+    }
+
+    #[test]
+    fn test_nullable_type_literal() {
+        // x.age is int?
+        let result = ExprParser::new().parse("x.age is int?").unwrap();
+        match result {
+            Expr::Binop(binop) => assert_eq!(
+                *binop.e2,
+                Expr::TypeLiteral(SimpleType::Nullable(Box::new(SimpleType::Base(BaseType::Int))))
+            ),
+            _ => panic!("Expected Binop"),
+        }
+        assert_eq!(format!("{}", SimpleTypeParser::new().parse("date?").unwrap()), "date?");
+    }
+
+    #[test]
+    fn test_union_type() {
+        // {id: int | str | null}
+        let result = PropertyTypeParser::new().parse("{id: int | str | null}").unwrap();
+        let int = SimpleType::Base(BaseType::Int);
+        let str = SimpleType::Base(BaseType::String);
+        let null = SimpleType::Base(BaseType::Null);
+        match result {
+            PropertyType::Open(map) => assert_eq!(
+                map.get("id"),
+                Some(&SimpleType::Union(
+                    Box::new(SimpleType::Union(Box::new(int.clone()), Box::new(str.clone()))),
+                    Box::new(null),
+                ))
+            ),
+            _ => panic!("Expected Open properties"),
+        }
+
+        // a nullable union prints as a union with null, which parses back
+        let nullable = SimpleType::Nullable(Box::new(SimpleType::Union(Box::new(int), Box::new(str))));
+        assert_eq!(format!("{}", nullable), "int | str | null");
+        assert_eq!(format!("{}", SimpleTypeParser::new().parse("int? | date").unwrap()), "int? | date");
+    }
+
+    #[test]
+    fn test_union_type_literal() {
+        // x.id IS int | str AND x.b IS bool | null
+        let result = ExprParser::new().parse("x.id IS int | str AND x.b IS bool | null").unwrap();
+        match result {
+            Expr::Binop(and) => {
+                assert_eq!(and.op, BinOpKind::And);
+                match (*and.e1, *and.e2) {
+                    (Expr::Binop(l), Expr::Binop(r)) => {
+                        assert_eq!(l.op, BinOpKind::Is);
+                        assert!(matches!(*l.e2, Expr::TypeLiteral(SimpleType::Union(_, _))));
+                        assert_eq!(r.op, BinOpKind::Is);
+                        assert!(matches!(*r.e2, Expr::TypeLiteral(SimpleType::Union(_, _))));
+                    }
+                    _ => panic!("Expected two Binops"),
+                }
+            }
+            _ => panic!("Expected Binop"),
+        }
+    }
+
+    #[test]
+    fn test_list_and_nested_record_types() {
+        // (x:{tags: list<str>, address: {city: str, zip?: int}, meta: {{a: {b: int}}}})
+        let result = PathPatternParser::new()
+            .parse("(x:{tags: list<str>, address: {city: str, zip?: int}, meta: {{a: {b: int}}}})")
+            .unwrap();
+        let node = match result {
+            ast::PathPattern::Node(n) => n,
+            _ => panic!("Expected PathPattern::Node"),
+        };
+        match &node.descriptor.descriptor_type.properties {
+            PropertyType::Open(map) => {
+                assert_eq!(
+                    map.get("tags"),
+                    Some(&SimpleType::List(Box::new(SimpleType::Base(BaseType::String))))
+                );
+                match map.get("address") {
+                    Some(SimpleType::Record(PropertyType::Open(address))) => {
+                        assert!(matches!(address.get("city"), Some(SimpleType::Base(BaseType::String))));
+                        assert!(address.is_optional("zip"));
+                    }
+                    _ => panic!("Expected nested Open record"),
+                }
+                match map.get("meta") {
+                    Some(SimpleType::Record(PropertyType::Closed(meta))) => {
+                        assert!(matches!(meta.get("a"), Some(SimpleType::Record(PropertyType::Open(_)))));
+                    }
+                    _ => panic!("Expected nested Closed record"),
+                }
+            }
+            _ => panic!("Expected Open properties"),
+        }
+    }
+
+    #[test]
+    fn test_list_and_nested_record_display() {
+        // Display output parses back to the same type
+        for input in [
+            "list<int>",
+            "list<list<str?>>",
+            "{city: str, zip?: int}",
+            "{{address: {{city: str}}, tags: list<str>}}",
+            "list<{a: int}> | null",
+        ] {
+            let ty = SimpleTypeParser::new().parse(input).unwrap();
+            assert_eq!(format!("{}", ty), input);
+            assert_eq!(SimpleTypeParser::new().parse(&format!("{}", ty)).unwrap(), ty);
+        }
+    }
+
+    #[test]
+    fn test_list_keyword_as_key() {
+        // {list: list<int>} and x.list.list: `list` is still a valid property name
+        let ty = SimpleTypeParser::new().parse("{list: list<int>}").unwrap();
+        assert_eq!(format!("{}", ty), "{list: list<int>}");
+        assert_eq!(format!("{}", ExprParser::new().parse("x.list.list").unwrap()), "x.list.list");
+        assert!(ExprParser::new().parse("x.list = [1]").is_ok());
+
+        // (list), (x:list): and as a variable or label
+        let result = PathPatternParser::new().parse("(list)-[e:list]->(y)").unwrap();
+        assert_eq!(result.variables()[0], &Var("list".to_string()));
+        let desc = DescriptorParser::new().parse("x:list {list: list<str>}").unwrap();
+        assert_eq!(desc.descriptor_type.label, LabelType::Label("list".to_string()));
+    }
+
     #[test]
     fn test_label_and() {
         // (:Person & Company) -> NodePattern(Descriptor(None, DescriptorType(AndLabel(Label("Person"), Label("Company")), OpenPropertyType())))
@@ -180,6 +371,412 @@ mod tests {
         }
     }
 
+    // ==========================================
+    // GRAPH PATTERN TESTS
+    // ==========================================
+
+    #[test]
+    fn test_graph_pattern() {
+        // (x)-[]->(y), (y)-[]->(z), (x)~[]~(z)
+        let result = GraphPatternParser::new().parse("(x)-[]->(y), (y)-[]->(z), (x)~[]~(z)").unwrap();
+        assert_eq!(result.paths.len(), 3);
+        assert!(result.filter.is_none());
+        assert!(result.paths.iter().all(|p| matches!(p, ast::PathPattern::Concat(_, _))));
+    }
+
+    #[test]
+    fn test_graph_pattern_where() {
+        // (x)->(y), (y)->(z) WHERE x.a > z.a: the filter belongs to the graph pattern
+        let result = GraphPatternParser::new().parse("(x)->(y), (y)->(z) WHERE x.a > z.a").unwrap();
+        assert_eq!(result.paths.len(), 2);
+        assert!(matches!(result.filter, Some(Expr::Binop(_))));
+    }
+
+    #[test]
+    fn test_graph_pattern_join_variables() {
+        // x, y and z are each shared by two of the three paths, e is not
+        let result = GraphPatternParser::new().parse("(x)-[e]->(y), (y)->(z), p = (x)~(z)").unwrap();
+        let names = |vars: Vec<&Var>| vars.into_iter().map(|v| v.0.clone()).collect::<Vec<_>>();
+        assert_eq!(names(result.variables()), ["x", "e", "y", "z", "p"]);
+        assert_eq!(names(result.join_variables()), ["x", "y", "z"]);
+    }
+
+    // ==========================================
+    // QUERY TESTS
+    // ==========================================
+
+    #[test]
+    fn test_query() {
+        // MATCH (x:Person)-[e:Knows]->(y) WHERE e.since > 2000 RETURN x.name AS name, y
+        let result = QueryParser::new()
+            .parse("MATCH (x:Person)-[e:Knows]->(y) WHERE e.since > 2000 RETURN x.name AS name, y")
+            .unwrap();
+        assert_eq!(result.pattern.paths.len(), 1);
+        assert!(result.pattern.filter.is_some());
+        assert_eq!(result.items.len(), 2);
+        assert!(matches!(result.items[0].expr, Expr::AttributeLookup(_)));
+        assert_eq!(result.items[0].alias, Some(Var("name".to_string())));
+        assert_eq!(result.items[1].expr, Expr::Variable(Var("y".to_string())));
+        assert_eq!(result.items[1].alias, None);
+    }
+
+    #[test]
+    fn test_query_alias_vs_cast() {
+        // x.age AS int is a cast, (x.age AS int) AS age is a cast with an alias
+        let result = QueryParser::new()
+            .parse("match (x), (y) return x.age as int, (x.age as int) as age")
+            .unwrap();
+        assert_eq!(result.pattern.paths.len(), 2);
+        match &result.items[0] {
+            ast::ReturnItem { expr: Expr::Binop(binop), alias: None } => {
+                assert_eq!(binop.op, BinOpKind::As);
+                assert!(matches!(*binop.e2, Expr::TypeLiteral(SimpleType::Base(BaseType::Int))));
+            }
+            _ => panic!("Expected a cast without alias"),
+        }
+        match &result.items[1] {
+            ast::ReturnItem { expr: Expr::Binop(binop), alias: Some(alias) } => {
+                assert_eq!(binop.op, BinOpKind::As);
+                assert_eq!(*alias, Var("age".to_string()));
+            }
+            _ => panic!("Expected a cast with alias"),
+        }
+    }
+
+    #[test]
+    fn test_query_alias_of_logical_item() {
+        // The alias names the whole item, even though AS binds tighter than AND/OR
+        let result = QueryParser::new()
+            .parse("MATCH (x) RETURN x.a AND x.b AS both, x.a OR x.b > 1 AS either, x.c")
+            .unwrap();
+        let expected = |input: &str| ExprParser::new().parse(input).unwrap();
+        assert_eq!(result.items[0].expr, expected("x.a AND x.b"));
+        assert_eq!(result.items[0].alias, Some(Var("both".to_string())));
+        assert_eq!(result.items[1].expr, expected("x.a OR x.b > 1"));
+        assert_eq!(result.items[1].alias, Some(Var("either".to_string())));
+        assert_eq!(result.items[2].alias, None);
+
+        // A cast inside the item stays a cast
+        let result = QueryParser::new().parse("MATCH (x) RETURN x.a AND x.b AS bool AS both").unwrap();
+        assert_eq!(result.items[0].expr, expected("x.a AND (x.b AS bool)"));
+        assert_eq!(result.items[0].alias, Some(Var("both".to_string())));
+
+        // Parentheses hide the AS from the return item
+        let result = QueryParser::new().parse("MATCH (x) RETURN (x AS y)").unwrap();
+        assert_eq!(result.items[0].expr, expected("x AS y"));
+        assert_eq!(result.items[0].alias, None);
+    }
+
+    #[test]
+    fn test_query_keywords_as_names() {
+        // (match)-[return]->(y): query keywords still work as variables and keys
+        let result = PathPatternParser::new().parse("(match)-[return]->(y WHERE y.MATCH = 1)").unwrap();
+        let names = |vars: Vec<&Var>| vars.into_iter().map(|v| v.0.clone()).collect::<Vec<_>>();
+        assert_eq!(names(result.variables()), ["match", "return", "y"]);
+
+        assert!(PropertyTypeParser::new().parse("{match: int}").unwrap().record().contains_key("match"));
+        assert_eq!(format!("{}", ExprParser::new().parse("x.return").unwrap()), "x.return");
+
+        let result = QueryParser::new().parse("MATCH (return) RETURN return.match AS match").unwrap();
+        assert_eq!(result.items[0].expr, ExprParser::new().parse("return.match").unwrap());
+        assert_eq!(result.items[0].alias, Some(Var("match".to_string())));
+    }
+
+    // ==========================================
+    // SUBTYPING TESTS
+    // ==========================================
+
+    #[test]
+    fn test_simple_type_subtyping() {
+        assert!(parse!(SimpleTypeParser, "int").is_subtype_of(&parse!(SimpleTypeParser, "*")));
+        assert!(!parse!(SimpleTypeParser, "*").is_subtype_of(&parse!(SimpleTypeParser, "int")));
+        assert!(parse!(SimpleTypeParser, "int").is_subtype_of(&parse!(SimpleTypeParser, "int | str")));
+        assert!(
+            parse!(SimpleTypeParser, "int?").is_subtype_of(&parse!(SimpleTypeParser, "str | int | null"))
+        );
+        assert!(parse!(SimpleTypeParser, "int | null").is_subtype_of(&parse!(SimpleTypeParser, "int?")));
+        assert!(!parse!(SimpleTypeParser, "int?").is_subtype_of(&parse!(SimpleTypeParser, "int")));
+        assert!(
+            parse!(SimpleTypeParser, "list<int>").is_subtype_of(&parse!(SimpleTypeParser, "list<int | str>"))
+        );
+        assert!(!parse!(SimpleTypeParser, "list<int>").is_subtype_of(&parse!(SimpleTypeParser, "int")));
+        assert!(
+            parse!(SimpleTypeParser, "{{a: int, b: str}}").is_subtype_of(&parse!(SimpleTypeParser, "{a: *}"))
+        );
+    }
+
+    #[test]
+    fn test_property_subtyping_width() {
+        // a closed record fits an open one with fewer keys
+        assert_eq!(
+            parse!(PropertyTypeParser, "{{a: int}}").is_subtype_of(&parse!(PropertyTypeParser, "{a: int}")),
+            Ok(())
+        );
+        assert_eq!(
+            parse!(PropertyTypeParser, "{{a: int, b: str}}")
+                .is_subtype_of(&parse!(PropertyTypeParser, "{a: int}")),
+            Ok(())
+        );
+        assert_eq!(
+            parse!(PropertyTypeParser, "{a: int, b: str}").is_subtype_of(&parse!(PropertyTypeParser, "{}")),
+            Ok(())
+        );
+        // a required key must be present
+        assert_eq!(
+            parse!(PropertyTypeParser, "{a: int}")
+                .is_subtype_of(&parse!(PropertyTypeParser, "{a: int, b: str}")),
+            Err(ast::SubtypeError::Key("b".to_string()))
+        );
+        // a closed record rejects extra keys and open records
+        assert_eq!(
+            parse!(PropertyTypeParser, "{{a: int, b: str}}")
+                .is_subtype_of(&parse!(PropertyTypeParser, "{{a: int}}")),
+            Err(ast::SubtypeError::Key("b".to_string()))
+        );
+        assert_eq!(
+            parse!(PropertyTypeParser, "{a: int}").is_subtype_of(&parse!(PropertyTypeParser, "{{a: int}}")),
+            Err(ast::SubtypeError::Open)
+        );
+        assert_eq!(
+            parse!(PropertyTypeParser, "{{a: int}}").is_subtype_of(&parse!(PropertyTypeParser, "{{a: int}}")),
+            Ok(())
+        );
+        // the first offending key in source order is reported
+        assert_eq!(
+            parse!(PropertyTypeParser, "{{c: int}}")
+                .is_subtype_of(&parse!(PropertyTypeParser, "{b: str, a: str}")),
+            Err(ast::SubtypeError::Key("b".to_string()))
+        );
+        assert_eq!(
+            parse!(PropertyTypeParser, "{{c: int, b: str, a: str}}")
+                .is_subtype_of(&parse!(PropertyTypeParser, "{{a: str}}")),
+            Err(ast::SubtypeError::Key("c".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_property_subtyping_depth_and_optional() {
+        // field types are compared with SimpleType subtyping, * is the top
+        assert_eq!(
+            parse!(PropertyTypeParser, "{{a: int}}").is_subtype_of(&parse!(PropertyTypeParser, "{a: *}")),
+            Ok(())
+        );
+        assert_eq!(
+            parse!(PropertyTypeParser, "{a: *}").is_subtype_of(&parse!(PropertyTypeParser, "{a: int}")),
+            Err(ast::SubtypeError::Key("a".to_string()))
+        );
+        assert_eq!(
+            parse!(PropertyTypeParser, "{{address: {{city: str, zip: int}}}}")
+                .is_subtype_of(&parse!(PropertyTypeParser, "{address: {city: str}}")),
+            Ok(())
+        );
+        assert_eq!(
+            parse!(PropertyTypeParser, "{address: {city: int}}")
+                .is_subtype_of(&parse!(PropertyTypeParser, "{address: {city: str}}")),
+            Err(ast::SubtypeError::Key("address".to_string()))
+        );
+        // optional keys
+        assert_eq!(
+            parse!(PropertyTypeParser, "{{a: int}}")
+                .is_subtype_of(&parse!(PropertyTypeParser, "{{a: int, b?: str}}")),
+            Ok(())
+        );
+        assert_eq!(
+            parse!(PropertyTypeParser, "{{a?: int}}").is_subtype_of(&parse!(PropertyTypeParser, "{a?: int}")),
+            Ok(())
+        );
+        assert_eq!(
+            parse!(PropertyTypeParser, "{{a?: int}}").is_subtype_of(&parse!(PropertyTypeParser, "{a: int}")),
+            Err(ast::SubtypeError::Key("a".to_string()))
+        );
+        assert_eq!(
+            parse!(PropertyTypeParser, "{}").is_subtype_of(&parse!(PropertyTypeParser, "{b?: str}")),
+            Err(ast::SubtypeError::Key("b".to_string()))
+        );
+    }
+
+    // ==========================================
+    // DESCRIPTOR TYPE MEET AND JOIN TESTS
+    // ==========================================
+
+    #[test]
+    fn test_descriptor_type_meet() {
+        let meet = parse!(DescriptorTypeParser, "Person {age: int}")
+            .meet(&parse!(DescriptorTypeParser, "Employee {{age: int, id: int}}"))
+            .unwrap();
+        assert_eq!(meet.label, LabelTypeParser::new().parse("Person & Employee").unwrap());
+        assert_eq!(meet.properties, parse!(PropertyTypeParser, "{{age: int, id: int}}"));
+
+        // Narrower type and required key win
+        let meet = parse!(DescriptorTypeParser, "{a?: int | str, b: str}")
+            .meet(&parse!(DescriptorTypeParser, "{a: int}"))
+            .unwrap();
+        assert_eq!(meet.properties, parse!(PropertyTypeParser, "{a: int, b: str}"));
+
+        // Optional keys outside a closed record are dropped
+        let meet = parse!(DescriptorTypeParser, "{a?: int}")
+            .meet(&parse!(DescriptorTypeParser, "{{b: str}}"))
+            .unwrap();
+        assert_eq!(meet.properties, parse!(PropertyTypeParser, "{{b: str}}"));
+
+        // Keys keep the order in which they first occur
+        let meet = parse!(DescriptorTypeParser, "{c: int, a: int}")
+            .meet(&parse!(DescriptorTypeParser, "{b: str, a: int}"))
+            .unwrap();
+        assert_eq!(meet.properties.record().keys().collect::<Vec<_>>(), ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_descriptor_type_join() {
+        let join = parse!(DescriptorTypeParser, "Person {{age: int, name: str}}")
+            .join(&parse!(DescriptorTypeParser, "Company {{age: int | str}}"))
+            .unwrap();
+        assert_eq!(join.label, LabelTypeParser::new().parse("Person | Company").unwrap());
+        assert_eq!(join.properties, parse!(PropertyTypeParser, "{{age: int | str, name?: str}}"));
+
+        // Keys of one side are dropped when the other side is open
+        let join = parse!(DescriptorTypeParser, "{{a: int, b: str}}")
+            .join(&parse!(DescriptorTypeParser, "{a: int?}"))
+            .unwrap();
+        assert_eq!(join.properties, parse!(PropertyTypeParser, "{a: int?}"));
+
+        let join = parse!(DescriptorTypeParser, "{{c: int, a: int}}")
+            .join(&parse!(DescriptorTypeParser, "{{b: str, a: int}}"))
+            .unwrap();
+        assert_eq!(join.properties.record().keys().collect::<Vec<_>>(), ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_descriptor_type_conflicts() {
+        let conflict = ast::DescriptorTypeError::PropertyConflict {
+            key: "age".to_string(),
+            left: Box::new(SimpleType::Base(BaseType::Int)),
+            right: Box::new(SimpleType::Base(BaseType::String)),
+        };
+        let t1 = parse!(DescriptorTypeParser, "Person {age: int}");
+        let t2 = parse!(DescriptorTypeParser, "Person {age: str}");
+        assert_eq!(t1.meet(&t2).unwrap_err(), conflict);
+        assert_eq!(t1.join(&t2).unwrap_err(), conflict);
+
+        assert_eq!(
+            parse!(DescriptorTypeParser, "{{a: int}}")
+                .meet(&parse!(DescriptorTypeParser, "{b: int}"))
+                .unwrap_err(),
+            ast::DescriptorTypeError::ClosedRecord("b".to_string())
+        );
+
+        // The first conflicting key in source order is reported
+        let t1 = parse!(DescriptorTypeParser, "{b: int, a: int}");
+        let t2 = parse!(DescriptorTypeParser, "{a: str, b: str}");
+        assert!(matches!(t1.meet(&t2), Err(ast::DescriptorTypeError::PropertyConflict { key, .. }) if key == "b"));
+    }
+
+    // ==========================================
+    // LABEL REASONING TESTS
+    // ==========================================
+
+    #[test]
+    fn test_label_satisfiability() {
+        assert!(parse!(LabelTypeParser, "Person").is_satisfiable());
+        assert!(parse!(LabelTypeParser, "*").is_satisfiable());
+        assert!(parse!(LabelTypeParser, "Person & Employee").is_satisfiable());
+        assert!(!parse!(LabelTypeParser, "Person & !Person").is_satisfiable());
+        assert!(!parse!(LabelTypeParser, "!*").is_satisfiable());
+        // A and B disjoint under the schema
+        assert!(!parse!(LabelTypeParser, "(A & B) & !(A & B)").is_satisfiable());
+        assert!(parse!(LabelTypeParser, "A & !(A & B)").is_satisfiable());
+        // % needs some label, possibly one that is never mentioned
+        assert!(parse!(LabelTypeParser, "% & !Person").is_satisfiable());
+        assert!(!parse!(LabelTypeParser, "Person & !%").is_satisfiable());
+        assert!(!parse!(LabelTypeParser, "% & !%").is_satisfiable());
+    }
+
+    #[test]
+    fn test_label_entailment() {
+        assert!(parse!(LabelTypeParser, "Person & Employee").entails(&parse!(LabelTypeParser, "Person")));
+        assert!(!parse!(LabelTypeParser, "Person").entails(&parse!(LabelTypeParser, "Person & Employee")));
+        assert!(parse!(LabelTypeParser, "Person").entails(&parse!(LabelTypeParser, "Person | Company")));
+        assert!(parse!(LabelTypeParser, "Person").entails(&parse!(LabelTypeParser, "*")));
+        assert!(parse!(LabelTypeParser, "Person").entails(&parse!(LabelTypeParser, "%")));
+        assert!(!parse!(LabelTypeParser, "*").entails(&parse!(LabelTypeParser, "%")));
+        assert!(parse!(LabelTypeParser, "Person & !Person").entails(&parse!(LabelTypeParser, "Anything")));
+    }
+
+    #[test]
+    fn test_label_equivalence() {
+        assert!(
+            parse!(LabelTypeParser, "A & (B | C)").equivalent(&parse!(LabelTypeParser, "(A & B) | (A & C)"))
+        );
+        assert!(parse!(LabelTypeParser, "!(A | B)").equivalent(&parse!(LabelTypeParser, "!A & !B")));
+        assert!(parse!(LabelTypeParser, "A | (A & B)").equivalent(&parse!(LabelTypeParser, "A")));
+        assert!(parse!(LabelTypeParser, "A & *").equivalent(&parse!(LabelTypeParser, "A")));
+        assert!(!parse!(LabelTypeParser, "A | B").equivalent(&parse!(LabelTypeParser, "A & B")));
+    }
+
+    #[test]
+    fn test_label_reasoning_many_labels() {
+        // Generated queries may mention far more labels than can be enumerated
+        let names: Vec<String> = (0..200).map(|i| format!("L{}", i)).collect();
+        let any = parse!(LabelTypeParser, &names.join(" | "));
+        let all = parse!(LabelTypeParser, &names.join(" & "));
+        assert!(any.is_satisfiable());
+        assert!(all.is_satisfiable());
+        assert!(all.entails(&any));
+        assert!(!any.entails(&all));
+        assert!(any.entails(&parse!(LabelTypeParser, "%")));
+        assert!(!parse!(LabelTypeParser, &format!("({}) & !%", names.join(" | "))).is_satisfiable());
+        assert!(parse!(LabelTypeParser, &format!("% & !({})", names.join(" | "))).is_satisfiable());
+    }
+
+    #[test]
+    fn test_label_dnf() {
+        assert_eq!(parse!(LabelTypeParser, "(A | A) & (A | B)").to_dnf(), parse!(LabelTypeParser, "A"));
+        assert_eq!(parse!(LabelTypeParser, "A & *").to_dnf(), parse!(LabelTypeParser, "A"));
+        assert_eq!(parse!(LabelTypeParser, "A | !*").to_dnf(), parse!(LabelTypeParser, "A"));
+        assert_eq!(
+            parse!(LabelTypeParser, "B & (C | A)").to_dnf(),
+            parse!(LabelTypeParser, "(A & B) | (B & C)")
+        );
+        assert_eq!(parse!(LabelTypeParser, "!(A & B)").to_dnf(), parse!(LabelTypeParser, "!A | !B"));
+        assert_eq!(parse!(LabelTypeParser, "(A & B) | (A & !B)").to_dnf(), parse!(LabelTypeParser, "A"));
+        assert_eq!(parse!(LabelTypeParser, "A | !A").to_dnf(), parse!(LabelTypeParser, "*"));
+        assert_eq!(parse!(LabelTypeParser, "A & !A").to_dnf(), parse!(LabelTypeParser, "!*"));
+        assert_eq!(parse!(LabelTypeParser, "Person & %").to_dnf(), parse!(LabelTypeParser, "Person"));
+        assert_eq!(parse!(LabelTypeParser, "Person & !%").to_dnf(), parse!(LabelTypeParser, "!*"));
+    }
+
+    #[test]
+    fn test_label_cnf() {
+        assert_eq!(parse!(LabelTypeParser, "(A & A) | (A & B)").to_cnf(), parse!(LabelTypeParser, "A"));
+        assert_eq!(
+            parse!(LabelTypeParser, "(A & B) | C").to_cnf(),
+            parse!(LabelTypeParser, "(A | C) & (B | C)")
+        );
+        assert_eq!(parse!(LabelTypeParser, "!(A | B)").to_cnf(), parse!(LabelTypeParser, "!A & !B"));
+        assert_eq!(parse!(LabelTypeParser, "A | !A").to_cnf(), parse!(LabelTypeParser, "*"));
+        assert_eq!(parse!(LabelTypeParser, "A & !A").to_cnf(), parse!(LabelTypeParser, "!*"));
+    }
+
+    #[test]
+    fn test_label_canonical_forms_agree() {
+        let pairs = [
+            ("A & (B | C)", "(C & A) | (A & B)"),
+            ("!(A | B)", "!B & !A"),
+            ("A | (A & B)", "A & (A | B)"),
+            ("(A | B) & (!A | C)", "(A & C) | (!A & B)"),
+            ("A | %", "%"),
+            ("(A & !%) | B", "B"),
+            ("A & %", "A"),
+            ("!A & (A | %)", "% & !A"),
+            ("A | !A", "*"),
+        ];
+        for (l1, l2) in pairs {
+            assert!(parse!(LabelTypeParser, l1).equivalent(&parse!(LabelTypeParser, l2)));
+            assert_eq!(parse!(LabelTypeParser, l1).to_dnf(), parse!(LabelTypeParser, l2).to_dnf());
+            assert_eq!(parse!(LabelTypeParser, l1).to_cnf(), parse!(LabelTypeParser, l2).to_cnf());
+        }
+    }
+
     // ==========================================
     // EXPRESSION TESTS (from parser_test.py filter tests)
     // ==========================================
@@ -226,6 +823,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_expr_less_than_negative() {
+        // x.a<-1 lexes as the arrow token `<-` but still means x.a < -1
+        for (input, spaced) in [
+            ("x.a<-1", "x.a < -1"),
+            ("x.a<-1+2*3", "x.a < -1+2*3"),
+            ("x.a<-2*3", "x.a < -2*3"),
+            ("x.a<-(1+2)", "x.a < -(1+2)"),
+            ("x.a<--1 AND x.b", "x.a < - -1 AND x.b"),
+        ] {
+            assert_eq!(ExprParser::new().parse(input).unwrap(), ExprParser::new().parse(spaced).unwrap());
+        }
+        assert!(PathPatternParser::new().parse("(x WHERE x.a<-1)<-(y)").is_ok());
+    }
+
     #[test]
     fn test_expr_precedence() {
         // 11 = 10 and 1 = 2 or 1=2
@@ -336,6 +948,159 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_additional_base_types() {
+        for (input, expected) in [
+            ("float", BaseType::Float),
+            ("date", BaseType::Date),
+            ("datetime", BaseType::DateTime),
+            ("duration", BaseType::Duration),
+            ("null", BaseType::Null),
+        ] {
+            let result = SimpleTypeParser::new().parse(input).unwrap();
+            assert_eq!(result, SimpleType::Base(expected));
+            assert_eq!(format!("{}", result), input);
+            assert_eq!(format!("{:?}", result), input);
+        }
+
+        // (x {price: float, since: date, nickname: null})
+        let result = PropertyTypeParser::new().parse("{price: float, since: date, nickname: null}").unwrap();
+        match result {
+            PropertyType::Open(map) => {
+                assert!(matches!(map.get("price"), Some(SimpleType::Base(BaseType::Float))));
+                assert!(matches!(map.get("since"), Some(SimpleType::Base(BaseType::Date))));
+                assert!(matches!(map.get("nickname"), Some(SimpleType::Base(BaseType::Null))));
+            }
+            _ => panic!("Expected Open properties"),
+        }
+    }
+
+    #[test]
+    fn test_expr_additional_literals() {
+        for (input, expected) in [
+            ("2.75", Constant::Float(2.75)),
+            ("DATE '2024-01-01'", Constant::Date("2024-01-01".to_string())),
+            ("datetime '2024-01-01T10:00:00'", Constant::DateTime("2024-01-01T10:00:00".to_string())),
+            ("DURATION 'P1D'", Constant::Duration("P1D".to_string())),
+            ("NULL", Constant::Null),
+            ("null", Constant::Null),
+        ] {
+            let result = ExprParser::new().parse(input).unwrap();
+            assert_eq!(result, Expr::Constant(expected));
+        }
+
+        // x is date: a bare type keyword is still a type literal
+        let result = ExprParser::new().parse("x is date").unwrap();
+        match result {
+            Expr::Binop(binop) => {
+                assert!(matches!(*binop.e2, Expr::TypeLiteral(SimpleType::Base(BaseType::Date))))
+            }
+            _ => panic!("Expected Binop"),
+        }
+    }
+
+    #[test]
+    fn test_expr_additional_literals_reparse() {
+        // Display output parses back to the same expression
+        for input in [
+            "x.price > 2.0",
+            "x.price = 3.25",
+            "x.since >= DATE '2024-01-01'",
+            "x.at < DATETIME '2024-01-01T10:00:00'",
+            "x.ttl = DURATION 'PT1H'",
+            "x.nickname = NULL",
+        ] {
+            let expr = ExprParser::new().parse(input).unwrap();
+            let printed = format!("{}", expr);
+            assert_eq!(ExprParser::new().parse(&printed).unwrap(), expr, "{}", printed);
+        }
+        assert_eq!(format!("{:?}", Constant::Float(2.0)), "2.0");
+        assert_eq!(format!("{:?}", Constant::Date("2024-01-01".to_string())), "DATE '2024-01-01'");
+    }
+
+    #[test]
+    fn test_type_keywords_as_keys() {
+        // x.date and {date: date}: type keywords are still valid property names
+        let result = ExprParser::new().parse("x.date >= DATE '2024-01-01' AND x.duration > 1").unwrap();
+        assert_eq!(
+            format!("{}", result),
+            "((x.date >= DATE '2024-01-01') AND (x.duration > 1))"
+        );
+        for input in ["x.date", "x.null", "x.float.DATETIME"] {
+            assert_eq!(format!("{}", ExprParser::new().parse(input).unwrap()), input);
+        }
+        let result = PathPatternParser::new().parse("(x:{date: date, NULL?: null, datetime: 'now'})").unwrap();
+        match result {
+            ast::PathPattern::Filter(p, _) => match *p {
+                ast::PathPattern::Node(n) => {
+                    let record = n.descriptor.descriptor_type.properties;
+                    assert_eq!(record.record().get("date"), Some(&SimpleType::Base(BaseType::Date)));
+                    assert!(record.record().is_optional("NULL"));
+                }
+                _ => panic!("Expected PathPattern::Node"),
+            },
+            _ => panic!("Expected PathPattern::Filter"),
+        }
+    }
+
+    #[test]
+    fn test_type_keywords_as_element_names() {
+        // (date), (float)-[e]->(y), (x:date): type keywords are still valid variables and labels
+        let result = PathPatternParser::new().parse("(date)").unwrap();
+        assert_eq!(result.variables(), [&Var("date".to_string())]);
+        let result = PathPatternParser::new().parse("(float)-[duration:DATETIME]->(NULL)").unwrap();
+        let names = |vars: Vec<&Var>| vars.into_iter().map(|v| v.0.clone()).collect::<Vec<_>>();
+        assert_eq!(names(result.variables()), ["float", "duration", "NULL"]);
+        assert!(PathPatternParser::new().parse("(null)").is_ok());
+
+        let desc = DescriptorParser::new().parse("x:date & !null").unwrap();
+        assert_eq!(desc.descriptor_type.label, LabelTypeParser::new().parse("date & !null").unwrap());
+        let desc = DescriptorParser::new().parse("datetime IS float {date: date}").unwrap();
+        assert_eq!(desc.variable, Some(Var("datetime".to_string())));
+        assert_eq!(desc.descriptor_type.label, LabelType::Label("float".to_string()));
+    }
+
+    #[test]
+    fn test_expr_list_literal() {
+        // x.tags = [1, 2 + 3, []]
+        let result = ExprParser::new().parse("x.tags = [1, 2 + 3, []]").unwrap();
+        match result {
+            Expr::Binop(binop) => match *binop.e2 {
+                Expr::List(ref items) => {
+                    assert_eq!(items.len(), 3);
+                    assert_eq!(items[0], Expr::Constant(Constant::Int(1)));
+                    assert!(matches!(items[1], Expr::Binop(_)));
+                    assert_eq!(items[2], Expr::List(Vec::new()));
+                }
+                _ => panic!("Expected List"),
+            },
+            _ => panic!("Expected Binop"),
+        }
+    }
+
+    #[test]
+    fn test_expr_chained_attribute_lookup() {
+        // x.address.city = 'Santiago' -> ((x.address).city)
+        let result = ExprParser::new().parse("x.address.city = 'Santiago'").unwrap();
+        match result {
+            Expr::Binop(binop) => match *binop.e1 {
+                Expr::AttributeLookup(ref lookup) => {
+                    assert_eq!(lookup.a, Var("city".to_string()));
+                    match *lookup.e {
+                        Expr::AttributeLookup(ref inner) => {
+                            assert_eq!(*inner.e, Expr::Variable(Var("x".to_string())));
+                            assert_eq!(inner.a, Var("address".to_string()));
+                        }
+                        _ => panic!("Expected nested AttributeLookup"),
+                    }
+                }
+                _ => panic!("Expected AttributeLookup"),
+            },
+            _ => panic!("Expected Binop"),
+        }
+        assert_eq!(format!("{}", ExprParser::new().parse("x.a.b.c").unwrap()), "x.a.b.c");
+    }
+
     #[test]
     fn test_expr_arithmetic() {
         // Test addition
@@ -387,67 +1152,847 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_descriptor_is_syntax() {
+        // (x IS Person & Employee {a: int}) is the same descriptor as (x:Person & Employee {a: int})
+        for input in ["(x IS Person & Employee {a: int})", "(x is Person & Employee {a: int})"] {
+            let result = PathPatternParser::new().parse(input).unwrap();
+            let colon = PathPatternParser::new().parse("(x:Person & Employee {a: int})").unwrap();
+            assert_eq!(format!("{:?}", result), format!("{:?}", colon));
+        }
+
+        // (IS Person WHERE x IS int): IS in the filter is still the type test operator
+        let result = PathPatternParser::new().parse("(IS Person WHERE x IS int)").unwrap();
+        match result {
+            ast::PathPattern::Filter(p, Expr::Binop(binop)) => {
+                assert_eq!(binop.op, BinOpKind::Is);
+                match *p {
+                    ast::PathPattern::Node(n) => {
+                        assert_eq!(n.descriptor.variable, None);
+                        assert!(matches!(&n.descriptor.descriptor_type.label, LabelType::Label(s) if s == "Person"));
+                    }
+                    _ => panic!("Expected PathPattern::Node"),
+                }
+            }
+            _ => panic!("Expected PathPattern::Filter"),
+        }
+    }
+
+    #[test]
+    fn test_descriptor_property_values() {
+        // (x:Person {name: 'Alice', age: 30}) -> Filter((x:Person {*}), x.name = 'Alice' AND x.age = 30)
+        let result = PathPatternParser::new().parse("(x:Person {name: 'Alice', age: 30})").unwrap();
+        let where_form = PathPatternParser::new()
+            .parse("(x:Person WHERE x.name = 'Alice' AND x.age = 30)")
+            .unwrap();
+        assert_eq!(format!("{:?}", result), format!("{:?}", where_form));
+        match result {
+            ast::PathPattern::Filter(p, _) => match *p {
+                ast::PathPattern::Node(n) => match &n.descriptor.descriptor_type.properties {
+                    PropertyType::Open(map) => assert!(map.is_empty()),
+                    _ => panic!("Expected Open properties"),
+                },
+                _ => panic!("Expected PathPattern::Node"),
+            },
+            _ => panic!("Expected PathPattern::Filter"),
+        }
+    }
+
+    #[test]
+    fn test_descriptor_property_values_mixed_with_types() {
+        // -[e:Knows {since: 2020, weight: int} WHERE e.weight > 1]->: types stay in the record,
+        // values are conjoined in front of the WHERE expression
+        let result = PathPatternParser::new()
+            .parse("-[e:Knows {since: 2020, weight: int} WHERE e.weight > 1]->")
+            .unwrap();
+        match result {
+            ast::PathPattern::Filter(p, Expr::Binop(binop)) => {
+                assert_eq!(binop.op, BinOpKind::And);
+                assert!(matches!(*binop.e1, Expr::Binop(ref eq) if eq.op == BinOpKind::Eq));
+                assert!(matches!(*binop.e2, Expr::Binop(ref gt) if gt.op == BinOpKind::Gt));
+                match *p {
+                    ast::PathPattern::Edge(e) => match &e.descriptor.descriptor_type.properties {
+                        PropertyType::Open(map) => {
+                            assert_eq!(map.len(), 1);
+                            assert!(matches!(map.get("weight"), Some(SimpleType::Base(BaseType::Int))));
+                        }
+                        _ => panic!("Expected Open properties"),
+                    },
+                    _ => panic!("Expected PathPattern::Edge"),
+                }
+            }
+            _ => panic!("Expected PathPattern::Filter"),
+        }
+    }
+
+    #[test]
+    fn test_descriptor_property_values_need_variable() {
+        // (:Person {name: 'Alice'}) has no variable to attach the filter to
+        match PathPatternParser::new().parse("(:Person {name: 'Alice'})") {
+            Err(lalrpop_util::ParseError::User { error }) => {
+                assert_eq!(error, error::SyntaxError::AnonymousPropertyValues)
+            }
+            _ => panic!("Expected SyntaxError::AnonymousPropertyValues"),
+        }
+    }
+
+    #[test]
+    fn test_descriptor_property_values_negative_and_null() {
+        // (x:{age: -1, score: -2.5, nickname: NULL}): NULL pins with IS, not =
+        let result = PathPatternParser::new().parse("(x:{age: -1, score: -2.5, nickname: NULL})").unwrap();
+        match result {
+            ast::PathPattern::Filter(_, values) => assert_eq!(
+                format!("{}", values),
+                "(((x.age = -1) AND (x.score = -2.5)) AND (x.nickname IS NULL))"
+            ),
+            _ => panic!("Expected PathPattern::Filter"),
+        }
+
+        // Only single constants can be pinned; lowercase null is the null type
+        let result = PathPatternParser::new().parse("(x:{nickname: null})").unwrap();
+        assert!(matches!(result, ast::PathPattern::Node(_)));
+        assert!(PathPatternParser::new().parse("(x:{tags: [1]})").is_err());
+    }
+
+    #[test]
+    fn test_label_not_and_wildcard() {
+        // (x:!Deleted & %) -> And(Not(Label("Deleted")), Wildcard)
+        let result = PathPatternParser::new().parse("(x:!Deleted & %)").unwrap();
+        let node = match result {
+            ast::PathPattern::Node(n) => n,
+            _ => panic!("Expected PathPattern::Node"),
+        };
+        assert_eq!(
+            node.descriptor.descriptor_type.label,
+            LabelType::And(
+                Box::new(LabelType::Not(Box::new(LabelType::Label("Deleted".to_string())))),
+                Box::new(LabelType::Wildcard),
+            )
+        );
+    }
+
+    #[test]
+    fn test_label_not_debug() {
+        // ! binds tighter than &, so parentheses are only printed where needed
+        for (input, expected) in [
+            ("!A & B", "(!A & B)"),
+            ("!(A & B)", "!(A & B)"),
+            ("!(A | B) & C", "(!(A | B) & C)"),
+            ("!!A | %", "(!!A | %)"),
+        ] {
+            let result = LabelTypeParser::new().parse(input).unwrap();
+            assert_eq!(format!("{:?}", result), expected);
+        }
+        assert_ne!(LabelTypeParser::new().parse("%").unwrap(), LabelType::Star);
+    }
+
+    // ==========================================
+    // EDGE PATTERN TESTS
+    // ==========================================
+
+    #[test]
+    fn test_edge_right_empty() {
+        // -> and -[]-> both parse to an empty right-pointing edge
+        for input in ["->", "-[]->"] {
+            let result = PathPatternParser::new().parse(input).unwrap();
+            let edge = match result {
+                ast::PathPattern::Edge(e) => e,
+                _ => panic!("Expected PathPattern::Edge"),
+            };
+            assert_eq!(edge.direction, EdgeDirection::Right);
+            assert_eq!(edge.descriptor.variable, None);
+            assert_eq!(edge.descriptor.descriptor_type.label, LabelType::Star);
+        }
+    }
+
+    #[test]
+    fn test_edge_left_empty() {
+        // <- and <-[]- both parse to an empty left-pointing edge
+        for input in ["<-", "<-[]-"] {
+            let result = PathPatternParser::new().parse(input).unwrap();
+            let edge = match result {
+                ast::PathPattern::Edge(e) => e,
+                _ => panic!("Expected PathPattern::Edge"),
+            };
+            assert_eq!(edge.direction, EdgeDirection::Left);
+            assert_eq!(edge.descriptor.variable, None);
+        }
+    }
+
+    #[test]
+    fn test_edge_non_directional() {
+        // ~ and ~[]~ both parse to an empty undirected edge
+        for input in ["~", "~[]~"] {
+            let result = PathPatternParser::new().parse(input).unwrap();
+            let edge = match result {
+                ast::PathPattern::Edge(e) => e,
+                _ => panic!("Expected PathPattern::Edge"),
+            };
+            assert_eq!(edge.direction, EdgeDirection::Undirected);
+            assert_eq!(edge.descriptor.variable, None);
+        }
+    }
+
+    #[test]
+    fn test_edge_descriptor() {
+        // -[e:Knows {since: int}]->
+        let result = PathPatternParser::new().parse("-[e:Knows {since: int}]->").unwrap();
+        let edge = match result {
+            ast::PathPattern::Edge(e) => e,
+            _ => panic!("Expected PathPattern::Edge"),
+        };
+        assert_eq!(edge.direction, EdgeDirection::Right);
+        let desc = &edge.descriptor;
+        assert_eq!(desc.variable, Some(Var("e".to_string())));
+        assert!(matches!(&desc.descriptor_type.label, LabelType::Label(s) if s == "Knows"));
+        match &desc.descriptor_type.properties {
+            PropertyType::Open(map) => {
+                assert_eq!(map.len(), 1);
+                assert!(matches!(map.get("since"), Some(SimpleType::Base(BaseType::Int))));
+            }
+            _ => panic!("Expected Open properties"),
+        }
+    }
+
+    #[test]
+    fn test_edge_where() {
+        // <-[e:Knows WHERE e.since > 2000]- and ~[e WHERE e.since > 2000]~
+        for (input, direction) in [
+            ("<-[e:Knows WHERE e.since > 2000]-", EdgeDirection::Left),
+            ("~[e WHERE e.since > 2000]~", EdgeDirection::Undirected),
+        ] {
+            let result = PathPatternParser::new().parse(input).unwrap();
+            match result {
+                ast::PathPattern::Filter(p, Expr::Binop(binop)) => {
+                    assert_eq!(binop.op, BinOpKind::Gt);
+                    match *p {
+                        ast::PathPattern::Edge(edge) => {
+                            assert_eq!(edge.direction, direction);
+                            assert_eq!(edge.descriptor.variable, Some(Var("e".to_string())));
+                        }
+                        _ => panic!("Expected PathPattern::Edge"),
+                    }
+                }
+                _ => panic!("Expected PathPattern::Filter"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_edge_all_directions() {
+        // full and abbreviated forms of the seven GQL orientations
+        for (full, abbreviated, direction) in [
+            ("<-[e]-", "<-", EdgeDirection::Left),
+            ("~[e]~", "~", EdgeDirection::Undirected),
+            ("-[e]->", "->", EdgeDirection::Right),
+            ("<~[e]~", "<~", EdgeDirection::LeftOrUndirected),
+            ("~[e]~>", "~>", EdgeDirection::RightOrUndirected),
+            ("<-[e]->", "<->", EdgeDirection::LeftOrRight),
+            ("-[e]-", "-", EdgeDirection::Any),
+        ] {
+            match PathPatternParser::new().parse(full).unwrap() {
+                ast::PathPattern::Edge(edge) => {
+                    assert_eq!(edge.direction, direction);
+                    assert_eq!(edge.descriptor.variable, Some(Var("e".to_string())));
+                }
+                _ => panic!("Expected PathPattern::Edge for {}", full),
+            }
+            match PathPatternParser::new().parse(abbreviated).unwrap() {
+                ast::PathPattern::Edge(edge) => {
+                    assert_eq!(edge.direction, direction);
+                    assert_eq!(edge.descriptor.variable, None);
+                }
+                _ => panic!("Expected PathPattern::Edge for {}", abbreviated),
+            }
+        }
+    }
+
+    #[test]
+    fn test_edge_any_direction_in_concatenation() {
+        // (x)-(y)<->(z): abbreviated any-direction and left-or-right edges
+        let result = PathPatternParser::new().parse("(x)-(y)<->(z)").unwrap();
+        assert_eq!(
+            format!("{:?}", result),
+            "(Descriptor(x, * {*})) -[Descriptor(None, * {*})]- (Descriptor(y, * {*})) \
+             <-[Descriptor(None, * {*})]-> (Descriptor(z, * {*}))"
+        );
+    }
+
+    // ==========================================
+    // CONCATENATION TESTS
+    // ==========================================
+
+    #[test]
+    fn test_concatenation() {
+        // (x)~[y]~(z) -> Concat(Concat((x), ~[y]~), (z))
+        let result = PathPatternParser::new().parse("(x)~[y]~(z)").unwrap();
+        let (left, right) = match result {
+            ast::PathPattern::Concat(l, r) => (l, r),
+            _ => panic!("Expected PathPattern::Concat"),
+        };
+        match *right {
+            ast::PathPattern::Node(n) => assert_eq!(n.descriptor.variable, Some(Var("z".to_string()))),
+            _ => panic!("Expected PathPattern::Node"),
+        }
+        match *left {
+            ast::PathPattern::Concat(l, r) => {
+                assert!(matches!(*l, ast::PathPattern::Node(_)));
+                match *r {
+                    ast::PathPattern::Edge(e) => {
+                        assert_eq!(e.direction, EdgeDirection::Undirected);
+                        assert_eq!(e.descriptor.variable, Some(Var("y".to_string())));
+                    }
+                    _ => panic!("Expected PathPattern::Edge"),
+                }
+            }
+            _ => panic!("Expected nested PathPattern::Concat"),
+        }
+    }
+
+    #[test]
+    fn test_concatenation_mixed_directions() {
+        // (x)-[e]->(y)~[f]~(z)<-(w)
+        let result = PathPatternParser::new().parse("(x)-[e]->(y)~[f]~(z)<-(w)").unwrap();
+        assert_eq!(
+            format!("{:?}", result),
+            "(Descriptor(x, * {*})) -[Descriptor(e, * {*})]-> (Descriptor(y, * {*})) \
+             ~[Descriptor(f, * {*})]~ (Descriptor(z, * {*})) <-[Descriptor(None, * {*})]- \
+             (Descriptor(w, * {*}))"
+        );
+    }
+
+    #[test]
+    fn test_filter_pattern() {
+        // (x WHERE x.a>10)
+        let result = PathPatternParser::new().parse("(x WHERE x.a>10)").unwrap();
+        match result {
+            ast::PathPattern::Filter(p, _) => assert!(matches!(*p, ast::PathPattern::Node(_))),
+            _ => panic!("Expected PathPattern::Filter"),
+        }
+    }
+
+    #[test]
+    fn test_filter_on_edge() {
+        // (x)-[y WHERE y.a>10]->(z)
+        let result = PathPatternParser::new().parse("(x)-[y WHERE y.a>10]->(z)").unwrap();
+        match result {
+            ast::PathPattern::Concat(l, _) => match *l {
+                ast::PathPattern::Concat(_, edge) => {
+                    assert!(matches!(*edge, ast::PathPattern::Filter(_, _)))
+                }
+                _ => panic!("Expected nested PathPattern::Concat"),
+            },
+            _ => panic!("Expected PathPattern::Concat"),
+        }
+    }
+
+    #[test]
+    fn test_filter_on_subpattern() {
+        // [(x)-[e]->(y) WHERE x.age > y.age]{1,3}
+        let result = PathPatternParser::new().parse("[(x)-[e]->(y) WHERE x.age > y.age]{1,3}").unwrap();
+        match result {
+            ast::PathPattern::Repeat { pattern, min: 1, max: Some(3) } => match *pattern {
+                ast::PathPattern::Filter(p, Expr::Binop(binop)) => {
+                    assert!(matches!(*p, ast::PathPattern::Concat(_, _)));
+                    assert_eq!(binop.op, BinOpKind::Gt);
+                }
+                _ => panic!("Expected PathPattern::Filter"),
+            },
+            _ => panic!("Expected PathPattern::Repeat"),
+        }
+    }
+
+    #[test]
+    fn test_filter_on_parenthesised_subpattern() {
+        // ((x)-[e]->(y) WHERE e.since > 2000)(z): the filter covers only the group
+        let result = PathPatternParser::new().parse("((x)-[e]->(y) WHERE e.since > 2000)(z)").unwrap();
+        match result {
+            ast::PathPattern::Concat(l, r) => {
+                assert!(matches!(*l, ast::PathPattern::Filter(_, _)));
+                assert!(matches!(*r, ast::PathPattern::Node(_)));
+            }
+            _ => panic!("Expected PathPattern::Concat"),
+        }
+    }
+
+    // ==========================================
+    // PATH VARIABLE TESTS
+    // ==========================================
+
+    #[test]
+    fn test_path_variable() {
+        // p = (x)-[e]->(y)
+        let result = PathPatternParser::new().parse("p = (x)-[e]->(y)").unwrap();
+        match &result {
+            ast::PathPattern::Named(v, p) => {
+                assert_eq!(*v, Var("p".to_string()));
+                assert!(matches!(**p, ast::PathPattern::Concat(_, _)));
+            }
+            _ => panic!("Expected PathPattern::Named"),
+        }
+        assert_eq!(result.path_variables(), [&Var("p".to_string())]);
+        assert!(result.variables().contains(&&Var("p".to_string())));
+    }
+
+    #[test]
+    fn test_path_variable_in_expr() {
+        // p = ((x)-[e]->(y) WHERE p = q) -> the filter refers to the path variable p
+        let result = PathPatternParser::new().parse("p = ((x)-[e]->(y) WHERE p = q)").unwrap();
+        match result {
+            ast::PathPattern::Named(_, p) => match *p {
+                ast::PathPattern::Filter(_, Expr::Binop(binop)) => {
+                    assert_eq!(*binop.e1, Expr::Variable(Var("p".to_string())));
+                }
+                _ => panic!("Expected PathPattern::Filter"),
+            },
+            _ => panic!("Expected PathPattern::Named"),
+        }
+    }
+
+    #[test]
+    fn test_subpath_variable() {
+        // (x) (q = (x)-[e]->(y))* binds the subpath q inside the repetition
+        let result = PathPatternParser::new().parse("(x) (q = (x)-[e]->(y))*").unwrap();
+        assert_eq!(result.path_variables(), [&Var("q".to_string())]);
+    }
+
+    // ==========================================
+    // RESTRICTOR TESTS
+    // ==========================================
+
+    #[test]
+    fn test_restrictors() {
+        // WALK / trail / SIMPLE / acyclic prefixes, both keyword cases
+        for (input, expected) in [
+            ("WALK (x)->*(y)", Restrictor::Walk),
+            ("trail (x)->*(y)", Restrictor::Trail),
+            ("SIMPLE (x)->*(y)", Restrictor::Simple),
+            ("acyclic (x)->*(y)", Restrictor::Acyclic),
+        ] {
+            let result = PathPatternParser::new().parse(input).unwrap();
+            match result {
+                ast::PathPattern::Restricted(r, p) => {
+                    assert_eq!(r, expected);
+                    assert!(matches!(*p, ast::PathPattern::Concat(_, _)));
+                }
+                _ => panic!("Expected PathPattern::Restricted for {}", input),
+            }
+        }
+    }
+
+    #[test]
+    fn test_restrictor_with_path_variable() {
+        // p = TRAIL (x)-[e]->+(y) | (x)~(y): the restrictor covers the whole union
+        let result = PathPatternParser::new().parse("p = TRAIL (x)-[e]->+(y) | (x)~(y)").unwrap();
+        match result {
+            ast::PathPattern::Named(_, p) => match *p {
+                ast::PathPattern::Restricted(Restrictor::Trail, p) => {
+                    assert!(matches!(*p, ast::PathPattern::Union(_, _)))
+                }
+                _ => panic!("Expected PathPattern::Restricted"),
+            },
+            _ => panic!("Expected PathPattern::Named"),
+        }
+    }
+
+    #[test]
+    fn test_restrictor_keywords_as_names() {
+        // (walk)-[simple]->(trail): restrictor keywords still work as variables, labels and keys
+        let result = PathPatternParser::new().parse("(walk)-[simple]->(trail WHERE trail.acyclic = 1)").unwrap();
+        let names = |vars: Vec<&Var>| vars.into_iter().map(|v| v.0.clone()).collect::<Vec<_>>();
+        assert_eq!(names(result.variables()), ["walk", "simple", "trail"]);
+
+        let result = PathPatternParser::new().parse("WALK = TRAIL (x)").unwrap();
+        assert!(matches!(result, ast::PathPattern::Named(Var(v), _) if v == "WALK"));
+        assert!(parse!(PropertyTypeParser, "{walk: int, SIMPLE?: str}").record().contains_key("SIMPLE"));
+        assert_eq!(format!("{}", ExprParser::new().parse("x.walk").unwrap()), "x.walk");
+        assert_eq!(
+            DescriptorParser::new().parse("x:walk & !TRAIL").unwrap().descriptor_type.label,
+            LabelTypeParser::new().parse("walk & !TRAIL").unwrap()
+        );
+        assert!(PathPatternParser::new().parse("(x:trail)-[e:SIMPLE | acyclic]->(y)").is_ok());
+    }
+
     // ==========================================
-    // TESTS FOR UNIMPLEMENTED FEATURES (commented out)
-    // ==========================================
-
-    // Edge patterns not yet implemented
-    // #[test]
-    // fn test_edge_right_empty() {
-    //     // -> or -[]->
-    //     let result = EdgePatternRightParser::new().parse("->").unwrap();
-    // }
-
-    // #[test]
-    // fn test_edge_left_empty() {
-    //     // <- or <-[]-
-    //     let result = EdgePatternLeftParser::new().parse("<-").unwrap();
-    // }
-
-    // #[test]
-    // fn test_edge_non_directional() {
-    //     // ~ or ~[]~
-    //     let result = EdgePatternUndirectedParser::new().parse("~").unwrap();
-    // }
-
-    // Concatenation patterns not yet implemented
-    // #[test]
-    // fn test_concatenation() {
-    //     // (x)~[y]~(z)
-    //     let result = PatternParser::new().parse("(x)~[y]~(z)").unwrap();
-    // }
-
-    // Filter patterns not yet implemented
-    // #[test]
-    // fn test_filter_pattern() {
-    //     // (x WHERE x.a>10)
-    //     let result = PatternParser::new().parse("(x WHERE x.a>10)").unwrap();
-    // }
-
-    // #[test]
-    // fn test_filter_on_edge() {
-    //     // (x)-[y WHERE y.a>10]->(z)
-    //     let result = PatternParser::new().parse("(x)-[y WHERE y.a>10]->(z)").unwrap();
-    // }
-
-    // Repetition patterns not yet implemented
-    // #[test]
-    // fn test_repetition() {
-    //     // (x)* or (x)+ or (x){1,2}
-    //     let result = PatternParser::new().parse("(x)*").unwrap();
-    // }
-
-    // Union patterns not yet implemented
-    // #[test]
-    // fn test_union() {
-    //     // () | ()
-    //     let result = PatternParser::new().parse("() | ()").unwrap();
-    // }
-
-    // Questioned patterns not yet implemented
-    // #[test]
-    // fn test_questioned_edge() {
-    //     // -[z]->?
-    //     let result = PatternParser::new().parse("-[z]->?").unwrap();
-    // }
+    // SELECTOR TESTS
+    // ==========================================
+
+    #[test]
+    fn test_selectors() {
+        for (input, expected) in [
+            ("ANY (x)->*(y)", Selector::Any(1)),
+            ("ANY 3 (x)->*(y)", Selector::Any(3)),
+            ("any shortest (x)->*(y)", Selector::AnyShortest),
+            ("ALL SHORTEST (x)->*(y)", Selector::AllShortest),
+            ("SHORTEST 2 (x)->*(y)", Selector::Shortest(2)),
+            ("SHORTEST 2 GROUPS (x)->*(y)", Selector::ShortestGroups(2)),
+        ] {
+            let result = PathPatternParser::new().parse(input).unwrap();
+            assert_eq!(result.selector(), Some(expected));
+            match result {
+                ast::PathPattern::Selected(_, p) => assert!(matches!(*p, ast::PathPattern::Concat(_, _))),
+                _ => panic!("Expected PathPattern::Selected for {}", input),
+            }
+        }
+    }
+
+    #[test]
+    fn test_selector_with_restrictor() {
+        // p = ANY SHORTEST TRAIL (x)-[e]->*(y)
+        let result = PathPatternParser::new().parse("p = ANY SHORTEST TRAIL (x)-[e]->*(y)").unwrap();
+        assert_eq!(result.selector(), Some(Selector::AnyShortest));
+        match result {
+            ast::PathPattern::Named(_, p) => match *p {
+                ast::PathPattern::Selected(_, p) => {
+                    assert!(matches!(*p, ast::PathPattern::Restricted(Restrictor::Trail, _)))
+                }
+                _ => panic!("Expected PathPattern::Selected"),
+            },
+            _ => panic!("Expected PathPattern::Named"),
+        }
+    }
+
+    #[test]
+    fn test_selector_rejected_in_subpattern() {
+        // (x) (ANY SHORTEST (x)->*(y)) -> selectors are top-level only
+        for input in ["(x) (ANY SHORTEST (x)->*(y))", "[q = SHORTEST 1 (x)->*(y) WHERE true]"] {
+            match PathPatternParser::new().parse(input) {
+                Err(lalrpop_util::ParseError::User { error }) => {
+                    assert!(matches!(error, error::SyntaxError::NestedSelector(_)))
+                }
+                _ => panic!("Expected SyntaxError::NestedSelector for {}", input),
+            }
+        }
+    }
+
+    #[test]
+    fn test_selector_keywords_as_names() {
+        // (any), (all)-[shortest]->(groups): selector keywords still work as variables, labels and keys
+        let result = PathPatternParser::new().parse("(any)").unwrap();
+        assert_eq!(result.variables(), [&Var("any".to_string())]);
+        let result = PathPatternParser::new().parse("(all)-[shortest]->(groups WHERE groups.any = x.all)").unwrap();
+        assert_eq!(result.variables().len(), 3);
+
+        let result = PathPatternParser::new().parse("ANY SHORTEST (any)-[e]->*(SHORTEST)").unwrap();
+        assert_eq!(result.selector(), Some(Selector::AnyShortest));
+        let result = PathPatternParser::new().parse("SHORTEST 2 GROUPS (groups)").unwrap();
+        assert_eq!(result.selector(), Some(Selector::ShortestGroups(2)));
+        assert!(parse!(PropertyTypeParser, "{any: int, ALL: str}").record().contains_key("ALL"));
+        assert_eq!(
+            DescriptorParser::new().parse("x:any | SHORTEST").unwrap().descriptor_type.label,
+            LabelTypeParser::new().parse("any | SHORTEST").unwrap()
+        );
+        assert!(PathPatternParser::new().parse("ANY (x:all)-[e:groups]->(y)").is_ok());
+    }
+
+    // ==========================================
+    // REPETITION TESTS
+    // ==========================================
+
+    #[test]
+    fn test_repetition() {
+        // (x)* (x)+ (x){2} (x){1,2} (x){3,}
+        for (input, expected_min, expected_max) in [
+            ("(x)*", 0, None),
+            ("(x)+", 1, None),
+            ("(x){2}", 2, Some(2)),
+            ("(x){1,2}", 1, Some(2)),
+            ("(x){3,}", 3, None),
+        ] {
+            let result = PathPatternParser::new().parse(input).unwrap();
+            match result {
+                ast::PathPattern::Repeat { pattern, min, max } => {
+                    assert!(matches!(*pattern, ast::PathPattern::Node(_)));
+                    assert_eq!(min, expected_min);
+                    assert_eq!(max, expected_max);
+                }
+                _ => panic!("Expected PathPattern::Repeat for {}", input),
+            }
+        }
+    }
+
+    #[test]
+    fn test_repetition_edge_in_concatenation() {
+        // (x)-[:Knows]->{1,3}(y): the quantifier binds to the edge only
+        let result = PathPatternParser::new().parse("(x)-[:Knows]->{1,3}(y)").unwrap();
+        match result {
+            ast::PathPattern::Concat(l, _) => match *l {
+                ast::PathPattern::Concat(_, r) => match *r {
+                    ast::PathPattern::Repeat { pattern, min: 1, max: Some(3) } => {
+                        assert!(matches!(*pattern, ast::PathPattern::Edge(_)))
+                    }
+                    _ => panic!("Expected PathPattern::Repeat"),
+                },
+                _ => panic!("Expected nested PathPattern::Concat"),
+            },
+            _ => panic!("Expected PathPattern::Concat"),
+        }
+    }
+
+    #[test]
+    fn test_repetition_debug_groups_concatenation() {
+        // ((x)-[e]->(y)){1,3} repeats the whole path, (x)-[e]->(y){1,3} only (y)
+        let grouped = PathPatternParser::new().parse("((x)-[e]->(y)){1,3}").unwrap();
+        let last = PathPatternParser::new().parse("(x)-[e]->(y){1,3}").unwrap();
+        assert_eq!(
+            format!("{:?}", grouped),
+            "((Descriptor(x, * {*})) -[Descriptor(e, * {*})]-> (Descriptor(y, * {*}))){1,3}"
+        );
+        assert_eq!(
+            format!("{:?}", last),
+            "(Descriptor(x, * {*})) -[Descriptor(e, * {*})]-> (Descriptor(y, * {*})){1,3}"
+        );
+    }
+
+    #[test]
+    fn test_repetition_star_label_not_confused() {
+        // (:*)* -> star label inside the node, postfix star outside
+        let result = PathPatternParser::new().parse("(:*)*").unwrap();
+        match result {
+            ast::PathPattern::Repeat { pattern, min: 0, max: None } => match *pattern {
+                ast::PathPattern::Node(n) => {
+                    assert_eq!(n.descriptor.descriptor_type.label, LabelType::Star)
+                }
+                _ => panic!("Expected PathPattern::Node"),
+            },
+            _ => panic!("Expected PathPattern::Repeat"),
+        }
+    }
+
+    #[test]
+    fn test_repetition_invalid_bounds() {
+        // (x){3,1} -> lower bound exceeds upper bound
+        let result = PathPatternParser::new().parse("(x){3,1}");
+        match result {
+            Err(lalrpop_util::ParseError::User { error }) => {
+                assert_eq!(error, error::SyntaxError::InvalidRepetition { min: 3, max: 1 });
+            }
+            _ => panic!("Expected SyntaxError::InvalidRepetition"),
+        }
+    }
+
+    #[test]
+    fn test_number_out_of_range() {
+        // Bounds beyond u32 and literals beyond i64 are errors, not panics
+        match PathPatternParser::new().parse("(x){4294967296}") {
+            Err(lalrpop_util::ParseError::User { error }) => {
+                assert_eq!(error, error::SyntaxError::RepetitionBoundOutOfRange(4294967296));
+            }
+            _ => panic!("Expected SyntaxError::RepetitionBoundOutOfRange"),
+        }
+        let digits = "99999999999999999999";
+        for input in [
+            format!("(x){{{}}}", digits),
+            format!("(x:P {{n: {}}})", digits),
+            format!("x.a > {}", digits),
+        ] {
+            let result = if input.starts_with('(') {
+                PathPatternParser::new().parse(&input).map(|_| ())
+            } else {
+                ExprParser::new().parse(&input).map(|_| ())
+            };
+            match result {
+                Err(lalrpop_util::ParseError::User { error }) => {
+                    assert_eq!(error, error::SyntaxError::IntegerOutOfRange(digits.to_string()));
+                }
+                _ => panic!("Expected SyntaxError::IntegerOutOfRange for {}", input),
+            }
+        }
+    }
+
+    // ==========================================
+    // UNION TESTS
+    // ==========================================
+
+    #[test]
+    fn test_union() {
+        // () | ()
+        let result = PathPatternParser::new().parse("() | ()").unwrap();
+        match result {
+            ast::PathPattern::Union(l, r) => {
+                assert!(matches!(*l, ast::PathPattern::Node(_)));
+                assert!(matches!(*r, ast::PathPattern::Node(_)));
+            }
+            _ => panic!("Expected PathPattern::Union"),
+        }
+    }
+
+    #[test]
+    fn test_union_labels_inside_descriptor() {
+        // (x:Person | Company) | (x:Company): the inner | is a label disjunction
+        let result = PathPatternParser::new().parse("(x:Person | Company) | (x:Company)").unwrap();
+        match result {
+            ast::PathPattern::Union(l, _) => match *l {
+                ast::PathPattern::Node(n) => assert!(matches!(
+                    n.descriptor.descriptor_type.label,
+                    LabelType::Or(_, _)
+                )),
+                _ => panic!("Expected PathPattern::Node"),
+            },
+            _ => panic!("Expected PathPattern::Union"),
+        }
+    }
+
+    #[test]
+    fn test_union_precedence() {
+        // (x)->(y) | (x)<-(y) -> Union(Concat(..), Concat(..))
+        let result = PathPatternParser::new().parse("(x)->(y) | (x)<-(y)").unwrap();
+        match result {
+            ast::PathPattern::Union(l, r) => {
+                assert!(matches!(*l, ast::PathPattern::Concat(_, _)));
+                assert!(matches!(*r, ast::PathPattern::Concat(_, _)));
+            }
+            _ => panic!("Expected PathPattern::Union"),
+        }
+    }
+
+    #[test]
+    fn test_multiset_union() {
+        // (x)->(y) |+| (x)->(y) | (z): left-associative
+        let result = PathPatternParser::new().parse("(x)->(y) |+| (x)->(y) | (z)").unwrap();
+        match result {
+            ast::PathPattern::Union(l, r) => {
+                assert!(matches!(*l, ast::PathPattern::MultisetUnion(_, _)));
+                assert!(matches!(*r, ast::PathPattern::Node(_)));
+            }
+            _ => panic!("Expected PathPattern::Union"),
+        }
+    }
+
+    // ==========================================
+    // OPTIONAL (QUESTIONED) TESTS
+    // ==========================================
+
+    #[test]
+    fn test_questioned_edge() {
+        // -[z]->?
+        let result = PathPatternParser::new().parse("-[z]->?").unwrap();
+        match result {
+            ast::PathPattern::Optional(p) => assert!(matches!(*p, ast::PathPattern::Edge(_))),
+            _ => panic!("Expected PathPattern::Optional"),
+        }
+    }
+
+    #[test]
+    fn test_questioned_node_and_subpattern() {
+        // (x)? and ((x)-[e]->(y))?
+        let result = PathPatternParser::new().parse("(x)?").unwrap();
+        match result {
+            ast::PathPattern::Optional(p) => assert!(matches!(*p, ast::PathPattern::Node(_))),
+            _ => panic!("Expected PathPattern::Optional"),
+        }
+
+        let result = PathPatternParser::new().parse("((x)-[e]->(y))?").unwrap();
+        match result {
+            ast::PathPattern::Optional(p) => assert!(matches!(*p, ast::PathPattern::Concat(_, _))),
+            _ => panic!("Expected PathPattern::Optional"),
+        }
+    }
+
+    #[test]
+    fn test_questioned_debug_groups_concatenation() {
+        // ((x)-[e]->(y))? makes the whole path optional, (x)-[e]->(y)? only (y)
+        let grouped = PathPatternParser::new().parse("((x)-[e]->(y))?").unwrap();
+        let last = PathPatternParser::new().parse("(x)-[e]->(y)?").unwrap();
+        assert_eq!(
+            format!("{:?}", grouped),
+            "((Descriptor(x, * {*})) -[Descriptor(e, * {*})]-> (Descriptor(y, * {*})))?"
+        );
+        assert_eq!(
+            format!("{:?}", last),
+            "(Descriptor(x, * {*})) -[Descriptor(e, * {*})]-> (Descriptor(y, * {*}))?"
+        );
+    }
+
+    #[test]
+    fn test_optional_variables() {
+        // (x)-[e]->(y) ((y)-[f]->(z))?: y is bound outside the optional part
+        let result = PathPatternParser::new().parse("(x)-[e]->(y) ((y)-[f]->(z))?").unwrap();
+        let names = |vars: Vec<&Var>| vars.into_iter().map(|v| v.0.clone()).collect::<Vec<_>>();
+        assert_eq!(names(result.variables()), ["x", "e", "y", "f", "z"]);
+        assert_eq!(names(result.optional_variables()), ["f", "z"]);
+    }
+
+    // ==========================================
+    // TYPE CHECKER TESTS
+    // ==========================================
+
+    // Types `input` with x and y bound to the descriptor types below
+    fn type_of(input: &str) -> Result<SimpleType, ast::TypeError> {
+        let env = [
+            "x:Person {{name: str, age?: int, score: float, address: {city: str}}}",
+            "y:Company {founded: date}",
+        ]
+        .into_iter()
+        .map(|input| {
+            let desc = parse!(DescriptorParser, input);
+            (desc.variable.unwrap(), desc.descriptor_type)
+        })
+        .collect();
+        parse!(ExprParser, input).type_of(&env)
+    }
+
+    #[test]
+    fn test_typecheck_well_typed() {
+        for (input, expected) in [
+            ("1 + 2 * 3", "int"),
+            ("1 + 2.5", "float"),
+            ("x.age + 1", "int?"),
+            ("-x.score", "float"),
+            ("x.address.city", "str"),
+            ("y.name", "*"),
+            ("y.name + 1", "*"),
+            ("x.age > 18 AND NOT (x.name = 'Bob')", "bool"),
+            ("y.founded < DATE '2000-01-01'", "bool"),
+            ("x.age = NULL", "bool"),
+            ("x.age IS NULL", "bool"),
+            ("x.age IS int?", "bool"),
+            ("x.age AS str", "str"),
+            ("[1, 2.5, 'a']", "list<int | float | str>"),
+            ("[x.age, 1]", "list<int?>"),
+        ] {
+            assert_eq!(type_of(input), Ok(parse!(SimpleTypeParser, expected)), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_typecheck_operator_mismatch() {
+        // 1 + true: the offending operand is true
+        assert_eq!(
+            type_of("1 + true"),
+            Err(ast::TypeError::Mismatch {
+                expr: Box::new(Expr::Constant(Constant::Bool(true))),
+                expected: Box::new(parse!(SimpleTypeParser, "int | float")),
+                found: Box::new(parse!(SimpleTypeParser, "bool")),
+            })
+        );
+        // 'a' AND 3: the left operand is checked first
+        match type_of("'a' AND 3") {
+            Err(ast::TypeError::Mismatch { expr, found, .. }) => {
+                assert_eq!(*expr, Expr::Constant(Constant::String("a".to_string())));
+                assert_eq!(*found, parse!(SimpleTypeParser, "str"));
+            }
+            other => panic!("Expected TypeError::Mismatch, got {:?}", other),
+        }
+        assert!(matches!(type_of("x.name.first"), Err(ast::TypeError::Mismatch { .. })));
+    }
+
+    #[test]
+    fn test_typecheck_errors() {
+        let err = type_of("x.age > 'b'").unwrap_err();
+        assert!(matches!(err, ast::TypeError::Incomparable { .. }));
+        assert_eq!(format!("{}", err), "`(x.age > 'b')` compares unrelated types int? and str");
+        let err = type_of("'a' AND 3").unwrap_err();
+        assert_eq!(format!("{}", err), "`'a'` has type str, expected bool");
+
+        assert_eq!(
+            type_of("x.salary"),
+            Err(ast::TypeError::NoSuchKey {
+                expr: Box::new(ExprParser::new().parse("x.salary").unwrap()),
+                key: "salary".to_string(),
+            })
+        );
+        assert_eq!(type_of("z.a"), Err(ast::TypeError::UnboundVariable(Var("z".to_string()))));
+        assert!(matches!(type_of("x.age IS 3"), Err(ast::TypeError::NotATypeLiteral(_))));
+    }
 }
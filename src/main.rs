@@ -10,6 +10,8 @@ fn main() {
     println!("  descriptor_type <input> - Parse as DescriptorType");
     println!("  descriptor <input> - Parse as Descriptor");
     println!("  path <input>       - Parse as PathPattern");
+    println!("  graph <input>      - Parse as GraphPattern");
+    println!("  query <input>      - Parse as Query");
     println!("  expr <input>       - Parse as Expr");
     println!("  quit               - Exit");
     println!();
@@ -80,6 +82,18 @@ fn main() {
                     Err(e) => eprintln!("✗ Parse error: {}", e),
                 }
             }
+            "graph" => {
+                match GraphPatternParser::new().parse(parse_input) {
+                    Ok(result) => println!("✓ Valid: {:?}", result),
+                    Err(e) => eprintln!("✗ Parse error: {}", e),
+                }
+            }
+            "query" => {
+                match QueryParser::new().parse(parse_input) {
+                    Ok(result) => println!("✓ Valid: {:?}", result),
+                    Err(e) => eprintln!("✗ Parse error: {}", e),
+                }
+            }
             "expr" => {
                 match ExprParser::new().parse(parse_input) {
                     Ok(result) => println!("✓ Valid: {:?}", result),
@@ -87,7 +101,7 @@ fn main() {
                 }
             }
             _ => {
-                eprintln!("Unknown command: {}. Use: label, simple, property, descriptor_type, descriptor, filler, node, or expr", command);
+                eprintln!("Unknown command: {}. Use: label, simple, property, descriptor_type, descriptor, path, graph, query, or expr", command);
             }
         }
     }